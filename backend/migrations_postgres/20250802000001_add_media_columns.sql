@@ -0,0 +1,4 @@
+-- RSSのenclosure / media:content / media:thumbnailから抽出した画像URL
+ALTER TABLE trade_news
+    ADD COLUMN IF NOT EXISTS image_url TEXT,
+    ADD COLUMN IF NOT EXISTS thumbnail_url TEXT;
@@ -0,0 +1,29 @@
+-- 記事内容の変更検知用ハッシュとリビジョン番号
+ALTER TABLE trade_news
+    ADD COLUMN IF NOT EXISTS content_hash TEXT,
+    ADD COLUMN IF NOT EXISTS revision INTEGER NOT NULL DEFAULT 1;
+
+-- 既存データのハッシュを埋める（NewsItem::content_hashと同じ計算式）
+UPDATE trade_news
+SET content_hash = encode(
+    sha256(convert_to(title || E'\n' || COALESCE(description, ''), 'UTF8')),
+    'hex'
+)
+WHERE content_hash IS NULL;
+
+-- 更新前の記事内容を保持する履歴テーブル
+CREATE TABLE IF NOT EXISTS trade_news_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    news_id TEXT NOT NULL REFERENCES trade_news(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    category TEXT,
+    title_ja TEXT,
+    description_ja TEXT,
+    content_hash TEXT,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (news_id, revision)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_news_revisions_news_id ON trade_news_revisions(news_id);
@@ -0,0 +1,63 @@
+-- db::modelsの型定義とスキーマを一致させる
+
+-- チームの日本語名
+ALTER TABLE teams
+    ADD COLUMN IF NOT EXISTS name_ja TEXT;
+
+UPDATE teams AS t
+SET name_ja = v.name_ja
+FROM (VALUES
+    ('BOS', 'ボストン・セルティックス'),
+    ('BKN', 'ブルックリン・ネッツ'),
+    ('NYK', 'ニューヨーク・ニックス'),
+    ('PHI', 'フィラデルフィア・76ers'),
+    ('TOR', 'トロント・ラプターズ'),
+    ('CHI', 'シカゴ・ブルズ'),
+    ('CLE', 'クリーブランド・キャバリアーズ'),
+    ('DET', 'デトロイト・ピストンズ'),
+    ('IND', 'インディアナ・ペイサーズ'),
+    ('MIL', 'ミルウォーキー・バックス'),
+    ('ATL', 'アトランタ・ホークス'),
+    ('CHA', 'シャーロット・ホーネッツ'),
+    ('MIA', 'マイアミ・ヒート'),
+    ('ORL', 'オーランド・マジック'),
+    ('WAS', 'ワシントン・ウィザーズ'),
+    ('DEN', 'デンバー・ナゲッツ'),
+    ('MIN', 'ミネソタ・ティンバーウルブズ'),
+    ('OKC', 'オクラホマシティ・サンダー'),
+    ('POR', 'ポートランド・トレイルブレイザーズ'),
+    ('UTA', 'ユタ・ジャズ'),
+    ('GSW', 'ゴールデンステート・ウォリアーズ'),
+    ('LAC', 'ロサンゼルス・クリッパーズ'),
+    ('LAL', 'ロサンゼルス・レイカーズ'),
+    ('PHX', 'フェニックス・サンズ'),
+    ('SAC', 'サクラメント・キングス'),
+    ('DAL', 'ダラス・マーベリックス'),
+    ('HOU', 'ヒューストン・ロケッツ'),
+    ('MEM', 'メンフィス・グリズリーズ'),
+    ('NOP', 'ニューオーリンズ・ペリカンズ'),
+    ('SAS', 'サンアントニオ・スパーズ')
+) AS v(id, name_ja)
+WHERE t.id = v.id AND t.name_ja IS NULL;
+
+ALTER TABLE teams
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ,
+    ALTER COLUMN updated_at TYPE TIMESTAMPTZ;
+
+-- 公式発表フラグ
+ALTER TABLE trade_news
+    ADD COLUMN IF NOT EXISTS is_official BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- アプリケーションが常に値を入れているカラムをNOT NULLにする
+UPDATE trade_news SET category = 'Other' WHERE category IS NULL;
+UPDATE trade_news SET translation_status = 'pending' WHERE translation_status IS NULL;
+
+ALTER TABLE trade_news
+    ALTER COLUMN category SET DEFAULT 'Other',
+    ALTER COLUMN category SET NOT NULL,
+    ALTER COLUMN translation_status SET NOT NULL;
+
+UPDATE trade_news_revisions SET category = 'Other' WHERE category IS NULL;
+
+ALTER TABLE trade_news_revisions
+    ALTER COLUMN category SET NOT NULL;
@@ -0,0 +1,18 @@
+-- 英語・日本語の全文検索
+
+-- 日本語はデフォルトのパーサーで単語に分割できないため、トライグラムで索引を作る
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- 英語のタイトル・説明文（タイトルを優先して順位付けする）
+ALTER TABLE trade_news
+    ADD COLUMN IF NOT EXISTS search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', COALESCE(title, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_trade_news_search_vector
+    ON trade_news USING GIN (search_vector);
+
+-- 日本語のタイトル・説明文（NewsStore::search_newsの検索式と一致させること）
+CREATE INDEX IF NOT EXISTS idx_trade_news_ja_trgm
+    ON trade_news USING GIN ((COALESCE(title_ja, '') || ' ' || COALESCE(description_ja, '')) gin_trgm_ops);
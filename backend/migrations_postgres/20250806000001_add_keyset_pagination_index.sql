@@ -0,0 +1,6 @@
+-- news接続のキーセットページング（published_at, idの降順）用のインデックス
+CREATE INDEX IF NOT EXISTS idx_trade_news_published_at_id
+    ON trade_news (published_at DESC, id DESC);
+
+-- 上のインデックスで代用できるため削除
+DROP INDEX IF EXISTS idx_trade_news_published_at;
@@ -0,0 +1,33 @@
+-- 記事と関連チームの多対多の関係（トレード記事は複数チームにまたがるため）
+CREATE TABLE IF NOT EXISTS trade_news_teams (
+    news_id TEXT NOT NULL REFERENCES trade_news(id) ON DELETE CASCADE,
+    team_id TEXT NOT NULL REFERENCES teams(id),
+    PRIMARY KEY (news_id, team_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_news_teams_team_id ON trade_news_teams(team_id);
+
+-- 既存の単一チーム列を移す
+INSERT INTO trade_news_teams (news_id, team_id)
+SELECT id, team_id
+FROM trade_news
+WHERE team_id IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+-- 既存記事のチームを判定する（scraper::teams::detect_team_idsと同じ規則）
+INSERT INTO trade_news_teams (news_id, team_id)
+SELECT n.id, names.team_id
+FROM trade_news AS n
+JOIN (
+    SELECT id AS team_id, name FROM teams
+    UNION ALL
+    SELECT * FROM (VALUES
+        ('PHI', 'Sixers'),
+        ('POR', 'Blazers'),
+        ('CLE', 'Cavs'),
+        ('DAL', 'Mavs')
+    ) AS aliases(team_id, name)
+) AS names
+    ON regexp_replace(n.title || ' ' || COALESCE(n.description, ''), '<[^>]+>', ' ', 'g')
+        ~ ('(^|[^[:alnum:]])' || names.name || '([^[:alnum:]]|$)')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,26 @@
+-- GraphQL APIのAPIキー（キー本体は保存せず、SHA-256のハッシュだけを持つ）
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    -- 一覧でキーを見分けるための先頭部分
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    role TEXT NOT NULL CHECK (role IN ('reader', 'editor', 'admin')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+-- 権限が必要な操作の監査ログ
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    -- 操作した主体（例: api_key:ci、jwt:alice、anonymous）
+    actor TEXT NOT NULL,
+    role TEXT,
+    action TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    details TEXT,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_recorded_at ON audit_log(recorded_at DESC);
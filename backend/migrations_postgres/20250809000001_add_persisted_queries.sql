@@ -0,0 +1,11 @@
+-- Automatic Persisted Queries（APQ）で登録されたクエリ
+-- 再起動後や他のインスタンスでも、クライアントがハッシュだけで実行できるようにする
+CREATE TABLE IF NOT EXISTS persisted_queries (
+    hash TEXT PRIMARY KEY,  -- クエリ本文のSHA-256（16進数）
+    query TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- GETレスポンスのETagに使う最終更新日時（MAX）を索引で求める
+CREATE INDEX IF NOT EXISTS idx_trade_news_scraped_at ON trade_news(scraped_at);
+CREATE INDEX IF NOT EXISTS idx_trade_news_translated_at ON trade_news(translated_at);
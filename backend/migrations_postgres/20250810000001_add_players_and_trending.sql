@@ -0,0 +1,88 @@
+-- 選手と、記事と選手の多対多の関係
+CREATE TABLE IF NOT EXISTS players (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    name_ja TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- scraper::players::NBA_PLAYERSと同じデータ
+INSERT INTO players (id, name, name_ja) VALUES
+    ('anthony-davis', 'Anthony Davis', 'アンソニー・デイビス'),
+    ('anthony-edwards', 'Anthony Edwards', 'アンソニー・エドワーズ'),
+    ('bam-adebayo', 'Bam Adebayo', 'バム・アデバヨ'),
+    ('damian-lillard', 'Damian Lillard', 'デイミアン・リラード'),
+    ('deaaron-fox', 'De''Aaron Fox', 'ディアロン・フォックス'),
+    ('devin-booker', 'Devin Booker', 'デビン・ブッカー'),
+    ('donovan-mitchell', 'Donovan Mitchell', 'ドノバン・ミッチェル'),
+    ('giannis-antetokounmpo', 'Giannis Antetokounmpo', 'ヤニス・アデトクンボ'),
+    ('ja-morant', 'Ja Morant', 'ジャ・モラント'),
+    ('jalen-brunson', 'Jalen Brunson', 'ジェイレン・ブランソン'),
+    ('james-harden', 'James Harden', 'ジェームズ・ハーデン'),
+    ('jayson-tatum', 'Jayson Tatum', 'ジェイソン・テイタム'),
+    ('jimmy-butler', 'Jimmy Butler', 'ジミー・バトラー'),
+    ('joel-embiid', 'Joel Embiid', 'ジョエル・エンビード'),
+    ('karl-anthony-towns', 'Karl-Anthony Towns', 'カール・アンソニー・タウンズ'),
+    ('kawhi-leonard', 'Kawhi Leonard', 'カワイ・レナード'),
+    ('kevin-durant', 'Kevin Durant', 'ケビン・デュラント'),
+    ('kyrie-irving', 'Kyrie Irving', 'カイリー・アービング'),
+    ('lauri-markkanen', 'Lauri Markkanen', 'ラウリ・マルカネン'),
+    ('lebron-james', 'LeBron James', 'レブロン・ジェームズ'),
+    ('luka-doncic', 'Luka Doncic', 'ルカ・ドンチッチ'),
+    ('nikola-jokic', 'Nikola Jokic', 'ニコラ・ヨキッチ'),
+    ('paul-george', 'Paul George', 'ポール・ジョージ'),
+    ('rui-hachimura', 'Rui Hachimura', '八村塁'),
+    ('shai-gilgeous-alexander', 'Shai Gilgeous-Alexander', 'シェイ・ギルジャス・アレクサンダー'),
+    ('stephen-curry', 'Stephen Curry', 'ステフィン・カリー'),
+    ('trae-young', 'Trae Young', 'トレイ・ヤング'),
+    ('tyrese-haliburton', 'Tyrese Haliburton', 'タイリース・ハリバートン'),
+    ('victor-wembanyama', 'Victor Wembanyama', 'ビクター・ウェンバンヤマ'),
+    ('yuki-kawamura', 'Yuki Kawamura', '河村勇輝'),
+    ('zach-lavine', 'Zach LaVine', 'ザック・ラビーン'),
+    ('zion-williamson', 'Zion Williamson', 'ザイオン・ウィリアムソン')
+ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS trade_news_players (
+    news_id TEXT NOT NULL REFERENCES trade_news(id) ON DELETE CASCADE,
+    player_id TEXT NOT NULL REFERENCES players(id),
+    PRIMARY KEY (news_id, player_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_news_players_player_id ON trade_news_players(player_id);
+
+-- 既存記事の選手を判定する（scraper::players::detect_player_idsと同じ規則）
+INSERT INTO trade_news_players (news_id, player_id)
+SELECT n.id, names.player_id
+FROM trade_news AS n
+JOIN (
+    SELECT id AS player_id, name FROM players
+    UNION ALL
+    SELECT * FROM (VALUES
+        ('luka-doncic', 'Luka Dončić'),
+        ('nikola-jokic', 'Nikola Jokić'),
+        ('shai-gilgeous-alexander', 'SGA')
+    ) AS aliases(player_id, name)
+) AS names
+    ON regexp_replace(n.title || ' ' || COALESCE(n.description, ''), '<[^>]+>', ' ', 'g')
+        ~ ('(^|[^[:alnum:]])' || names.name || '([^[:alnum:]]|$)')
+ON CONFLICT DO NOTHING;
+
+-- スケジューラーが計算したトレンドのランキング（集計期間ごとに最新の1件）
+CREATE TABLE IF NOT EXISTS trending_snapshots (
+    time_window TEXT PRIMARY KEY,
+    computed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trending_entries (
+    time_window TEXT NOT NULL REFERENCES trending_snapshots(time_window) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    entity_kind TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    name_ja TEXT,
+    mentions BIGINT NOT NULL,
+    weighted_mentions DOUBLE PRECISION NOT NULL,
+    baseline DOUBLE PRECISION NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (time_window, rank)
+);
@@ -0,0 +1,30 @@
+-- 編集者による上書き（分類器の出力とは別に保存し、再分類のたびに反映し直す）
+CREATE TABLE IF NOT EXISTS news_overrides (
+    news_id TEXT PRIMARY KEY REFERENCES trade_news(id) ON DELETE CASCADE,
+    -- NULLの項目は分類器の出力のまま
+    category TEXT,
+    team_ids TEXT[],
+    player_ids TEXT[],
+    is_official BOOLEAN,
+    hidden BOOLEAN,
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 上書きの変更履歴（教師データの出どころにもなる）
+CREATE TABLE IF NOT EXISTS news_override_log (
+    id BIGSERIAL PRIMARY KEY,
+    news_id TEXT NOT NULL REFERENCES trade_news(id) ON DELETE CASCADE,
+    field TEXT NOT NULL,
+    previous_value TEXT,
+    -- NULLは上書きを取り消して分類器の出力に戻したもの
+    new_value TEXT,
+    editor TEXT NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_news_override_log_news_id
+    ON news_override_log(news_id, changed_at DESC);
+
+-- 非表示にした記事は公開の一覧・検索・集計から除く
+ALTER TABLE trade_news ADD COLUMN IF NOT EXISTS hidden BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,23 @@
+-- スクレイピング・翻訳のバックグラウンドジョブ
+-- ミューテーションはジョブを登録してすぐに返り、進捗はこのテーブルに書き込まれる
+-- （どのインスタンスでも`job`クエリ・`jobProgress`サブスクリプションで参照できる）
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,                -- UUID v4
+    kind TEXT NOT NULL,                 -- scrape / translate
+    status TEXT NOT NULL,               -- queued / running / succeeded / failed
+    requested_by TEXT NOT NULL,         -- 登録した主体（監査ログの actor と同じ形式）
+    processed INTEGER NOT NULL DEFAULT 0,
+    total INTEGER,                      -- 処理対象の数（未確定なら NULL）
+    saved_count INTEGER NOT NULL DEFAULT 0,
+    updated_count INTEGER NOT NULL DEFAULT 0,
+    skipped_count INTEGER NOT NULL DEFAULT 0,
+    translated_count INTEGER NOT NULL DEFAULT 0,
+    errors TEXT[] NOT NULL DEFAULT '{}', -- 記事・フィードごとのエラー
+    failure TEXT,                       -- ジョブ全体が失敗した理由
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_kind_status ON jobs(kind, status);
@@ -0,0 +1,22 @@
+-- ジョブの記事・フィードごとのエラーに、対象・処理段階・コードを持たせる
+-- （errors はメッセージのまま、同じ位置の要素が1件のエラーを表す）
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS error_item_ids TEXT[] NOT NULL DEFAULT '{}', -- 記事ID（フィードのエラーではソース名）
+    ADD COLUMN IF NOT EXISTS error_stages TEXT[] NOT NULL DEFAULT '{}',   -- fetch / parse / validate / save / translate_title / translate_description
+    ADD COLUMN IF NOT EXISTS error_codes TEXT[] NOT NULL DEFAULT '{}',    -- FEED_FETCH_FAILED など
+    ADD COLUMN IF NOT EXISTS failure_code TEXT;                           -- ジョブ全体が失敗した理由のコード
+
+-- 既存のエラー（"ID: メッセージ"）は種類が分からないため INTERNAL_SERVER_ERROR とする
+UPDATE jobs SET
+    error_item_ids = ARRAY(
+        SELECT split_part(e.message, ': ', 1)
+        FROM unnest(errors) WITH ORDINALITY AS e(message, position)
+        ORDER BY e.position
+    ),
+    error_stages = array_fill(
+        (CASE kind WHEN 'scrape' THEN 'fetch' ELSE 'translate_title' END)::TEXT,
+        ARRAY[cardinality(errors)]
+    ),
+    error_codes = array_fill('INTERNAL_SERVER_ERROR'::TEXT, ARRAY[cardinality(errors)]),
+    failure_code = CASE WHEN failure IS NULL THEN NULL ELSE 'INTERNAL_SERVER_ERROR' END
+WHERE cardinality(error_codes) <> cardinality(errors) OR (failure IS NOT NULL AND failure_code IS NULL);
@@ -0,0 +1,12 @@
+-- RSSフィードごとの最新の取得状態（ヘルスチェックで参照する）
+-- スクレイピングを実行したインスタンスに関係なく、最後の取得結果で上書きする
+CREATE TABLE IF NOT EXISTS feed_status (
+    source TEXT PRIMARY KEY,                      -- ニュースソース名（ESPN、RealGMなど）
+    url TEXT NOT NULL,
+    last_attempt_at TIMESTAMPTZ NOT NULL,
+    last_success_at TIMESTAMPTZ,                  -- 最後に取得できた日時（一度もなければ NULL）
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    last_item_count INTEGER NOT NULL DEFAULT 0,   -- 最後に取得できた記事の数
+    last_error TEXT,                              -- 最後の取得が失敗した理由（成功なら NULL）
+    last_error_code TEXT                          -- FEED_FETCH_FAILED など
+);
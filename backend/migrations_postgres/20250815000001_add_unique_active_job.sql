@@ -0,0 +1,20 @@
+-- 同じ種類のジョブが同時に実行待ち・実行中にならないよう、データベースで保証する
+-- （複数のリクエスト・インスタンスが同時に登録しても、登録できるのは1つだけ）
+
+-- 既に重複しているものは、最も新しいものだけを残して失敗にする
+UPDATE jobs SET
+    status = 'failed',
+    failure = 'Job stopped making progress',
+    failure_code = 'INTERNAL_SERVER_ERROR',
+    finished_at = NOW(),
+    updated_at = NOW()
+WHERE status IN ('queued', 'running')
+  AND id NOT IN (
+    SELECT DISTINCT ON (kind) id
+    FROM jobs
+    WHERE status IN ('queued', 'running')
+    ORDER BY kind, created_at DESC
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_active_kind ON jobs(kind)
+    WHERE status IN ('queued', 'running');
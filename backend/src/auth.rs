@@ -0,0 +1,463 @@
+//! 認証とロールによる認可
+//!
+//! GraphQLエンドポイントへのリクエストは、`X-API-Key`ヘッダーのAPIキーか、
+//! `Authorization: Bearer`ヘッダーのAPIキーまたはJWT（HS256、`JWT_SECRET`で署名）で
+//! 認証します。認証情報のないリクエストは匿名として扱い、参照系のクエリだけを
+//! 実行できます。
+//!
+//! ミューテーションと管理用フィールドは[`RoleGuard`]でロールを確認し、
+//! 実行結果（拒否を含む）を監査ログに記録します。
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_graphql::{Context, Enum, ErrorExtensions, Guard};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{error, warn};
+
+use crate::db::{NewAuditEntry, SharedAuthRepository};
+use crate::errors::{AppError, ErrorCode};
+
+/// APIキーの接頭辞（ログやリポジトリでキーを見分けやすくするため）
+pub const API_KEY_PREFIX: &str = "ntk_";
+
+/// 一覧表示用に保存するキー先頭部分の文字数
+const KEY_PREFIX_LEN: usize = 12;
+
+/// APIキーを渡すヘッダー
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// ロール（下に行くほど強い権限を持ち、上位は下位の権限をすべて含む）
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 参照のみ
+    Reader,
+    /// スクレイピング・翻訳などの更新操作
+    Editor,
+    /// APIキー・監査ログの閲覧などの管理操作
+    Admin,
+}
+
+impl Role {
+    /// データベース・JWT上の表記
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Reader => "reader",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = AuthError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reader" => Ok(Role::Reader),
+            "editor" => Ok(Role::Editor),
+            "admin" => Ok(Role::Admin),
+            _ => Err(AuthError::UnknownRole(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 認証済みのリクエスト元
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// 監査ログに記録する主体（例: api_key:ci、jwt:alice）
+    pub actor: String,
+    pub role: Role,
+}
+
+impl Principal {
+    /// APIキーで認証された主体
+    pub fn api_key(name: &str, role: Role) -> Self {
+        Self {
+            actor: format!("api_key:{name}"),
+            role,
+        }
+    }
+
+    /// JWTで認証された主体
+    pub fn jwt(subject: &str, role: Role) -> Self {
+        Self {
+            actor: format!("jwt:{subject}"),
+            role,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("Invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("JWT authentication is not configured")]
+    JwtDisabled,
+    #[error("Unknown role: {0}")]
+    UnknownRole(String),
+    #[error("Failed to look up credentials: {0}")]
+    Repository(#[from] anyhow::Error),
+}
+
+impl AuthError {
+    /// リクエスト側の認証情報が誤っているか（それ以外はサーバー側の障害）
+    pub fn is_client_error(&self) -> bool {
+        !matches!(self, AuthError::Repository(_))
+    }
+}
+
+/// JWTのクレーム
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    /// 利用者の識別子
+    pub sub: String,
+    pub role: Role,
+    /// 有効期限（UNIX時間）
+    pub exp: i64,
+}
+
+/// 新しいAPIキーを生成（`ntk_`に続く32バイトの乱数の16進表記）
+pub fn generate_api_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{API_KEY_PREFIX}{hex}")
+}
+
+/// APIキーのハッシュ（データベースにはキー本体ではなくこの値を保存する）
+///
+/// キーは十分な長さの乱数なので、ソルトなしのSHA-256で総当たりは現実的でありません。
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// 一覧表示用のキー先頭部分
+pub fn api_key_prefix(key: &str) -> String {
+    key.chars().take(KEY_PREFIX_LEN).collect()
+}
+
+/// JWTを発行（管理CLIから使う）
+pub fn issue_token(
+    secret: &str,
+    subject: &str,
+    role: Role,
+    ttl: Duration,
+) -> Result<String, AuthError> {
+    let claims = Claims {
+        sub: subject.to_string(),
+        role,
+        exp: (Utc::now() + ttl).timestamp(),
+    };
+    Ok(jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?)
+}
+
+/// リクエストヘッダーから認証情報を取り出して検証する
+pub struct Authenticator {
+    repository: SharedAuthRepository,
+    jwt_secret: Option<String>,
+}
+
+impl Authenticator {
+    /// `jwt_secret`が`None`の場合、JWTは受け付けずAPIキーだけで認証します
+    pub fn new(repository: SharedAuthRepository, jwt_secret: Option<String>) -> Self {
+        Self {
+            repository,
+            jwt_secret,
+        }
+    }
+
+    /// 環境変数`JWT_SECRET`を使って作成
+    pub fn from_env(repository: SharedAuthRepository) -> Self {
+        let jwt_secret = std::env::var("JWT_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty());
+        Self::new(repository, jwt_secret)
+    }
+
+    /// 認証情報があれば検証して主体を返す（なければ`None`）
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<Option<Principal>, AuthError> {
+        if let Some(key) = header_value(headers, API_KEY_HEADER) {
+            return self.authenticate_api_key(key).await.map(Some);
+        }
+
+        let Some(token) = header_value(headers, header::AUTHORIZATION.as_str())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+        else {
+            return Ok(None);
+        };
+
+        if token.starts_with(API_KEY_PREFIX) {
+            self.authenticate_api_key(token).await.map(Some)
+        } else {
+            self.authenticate_jwt(token).map(Some)
+        }
+    }
+
+    async fn authenticate_api_key(&self, key: &str) -> Result<Principal, AuthError> {
+        let key = self
+            .repository
+            .find_active_api_key(&hash_api_key(key))
+            .await?
+            .ok_or(AuthError::InvalidApiKey)?;
+
+        Ok(Principal::api_key(&key.name, key.role.parse()?))
+    }
+
+    fn authenticate_jwt(&self, token: &str) -> Result<Principal, AuthError> {
+        let secret = self.jwt_secret.as_ref().ok_or(AuthError::JwtDisabled)?;
+        let claims = jsonwebtoken::decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )?
+        .claims;
+
+        Ok(Principal::jwt(&claims.sub, claims.role))
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// 認証ミドルウェア
+///
+/// 認証に成功した場合は[`Principal`]をリクエストの拡張に入れます。
+/// 認証情報が誤っている場合は、GraphQLのエラー形式で401を返します。
+pub async fn authenticate(
+    State(authenticator): State<Arc<Authenticator>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match authenticator.authenticate(request.headers()).await {
+        Ok(principal) => {
+            if let Some(principal) = principal {
+                request.extensions_mut().insert(principal);
+            }
+            next.run(request).await
+        }
+        Err(e) if e.is_client_error() => {
+            warn!("Rejected request with invalid credentials: {}", e);
+            error_response(
+                StatusCode::UNAUTHORIZED,
+                &e.to_string(),
+                ErrorCode::Unauthenticated.as_str(),
+            )
+        }
+        Err(e) => {
+            error!("Authentication failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Authentication failed",
+                ErrorCode::Internal.as_str(),
+            )
+        }
+    }
+}
+
+pub(crate) fn error_response(status: StatusCode, message: &str, code: &str) -> Response {
+    let body = serde_json::json!({
+        "errors": [{ "message": message, "extensions": { "code": code } }]
+    });
+    (status, Json(body)).into_response()
+}
+
+/// 必要なロールを持たないリクエストを拒否するガード
+///
+/// 拒否したリクエストも監査ログに記録します。
+pub struct RoleGuard {
+    required: Role,
+}
+
+impl RoleGuard {
+    pub fn new(required: Role) -> Self {
+        Self { required }
+    }
+}
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let error = match ctx.data_opt::<Principal>() {
+            Some(principal) if principal.role >= self.required => return Ok(()),
+            Some(principal) => AppError::Forbidden(format!(
+                "Role {} is required, but {} has role {}",
+                self.required, principal.actor, principal.role
+            ))
+            .extend(),
+            None => AppError::Unauthenticated.extend(),
+        };
+
+        record_audit(ctx, false, Some(error.message.clone())).await;
+        Err(error)
+    }
+}
+
+/// 権限が必要な操作の結果を監査ログに残す
+///
+/// 記録に失敗しても操作自体は失敗させず、エラーログだけを出します。
+pub async fn audited<T>(
+    ctx: &Context<'_>,
+    operation: impl std::future::Future<Output = async_graphql::Result<T>>,
+) -> async_graphql::Result<T> {
+    let result = operation.await;
+    let details = result.as_ref().err().map(|e| e.message.clone());
+    record_audit(ctx, result.is_ok(), details).await;
+    result
+}
+
+/// 実行中のフィールドを操作として監査ログに記録
+async fn record_audit(ctx: &Context<'_>, success: bool, details: Option<String>) {
+    let Ok(repository) = ctx.data::<SharedAuthRepository>() else {
+        error!("Audit log is not configured");
+        return;
+    };
+    let principal = ctx.data_opt::<Principal>();
+    let entry = NewAuditEntry {
+        actor: principal.map_or_else(|| "anonymous".to_string(), |p| p.actor.clone()),
+        role: principal.map(|p| p.role),
+        action: ctx.field().name().to_string(),
+        success,
+        details,
+    };
+
+    if let Err(e) = repository.record_audit(entry).await {
+        error!("Failed to write audit log: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{AuthRepository, InMemoryAuthStore};
+
+    const SECRET: &str = "test-secret";
+
+    async fn authenticator_with_key(role: Role) -> (Authenticator, String) {
+        let store = Arc::new(InMemoryAuthStore::new());
+        let key = generate_api_key();
+        store
+            .create_api_key("ci", role, &hash_api_key(&key), &api_key_prefix(&key))
+            .await
+            .unwrap();
+        (Authenticator::new(store, Some(SECRET.to_string())), key)
+    }
+
+    fn headers(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_role_order_and_parse() {
+        assert!(Role::Admin > Role::Editor && Role::Editor > Role::Reader);
+        assert_eq!("editor".parse::<Role>().unwrap(), Role::Editor);
+        assert!("root".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_generated_keys_are_unique_and_hashed() {
+        let key = generate_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + 64);
+        assert_ne!(key, generate_api_key());
+
+        let hash = hash_api_key(&key);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_api_key(&key));
+        assert_eq!(api_key_prefix(&key), key[..KEY_PREFIX_LEN]);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_api_key() {
+        let (authenticator, key) = authenticator_with_key(Role::Editor).await;
+        let expected = Some(Principal::api_key("ci", Role::Editor));
+
+        let principal = authenticator
+            .authenticate(&headers(
+                header::HeaderName::from_static(API_KEY_HEADER),
+                &key,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(principal, expected);
+
+        let principal = authenticator
+            .authenticate(&headers(header::AUTHORIZATION, &format!("Bearer {key}")))
+            .await
+            .unwrap();
+        assert_eq!(principal, expected);
+
+        let result = authenticator
+            .authenticate(&headers(
+                header::HeaderName::from_static(API_KEY_HEADER),
+                "ntk_wrong",
+            ))
+            .await;
+        assert!(matches!(result, Err(AuthError::InvalidApiKey)));
+
+        assert_eq!(
+            authenticator.authenticate(&HeaderMap::new()).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_jwt() {
+        let (authenticator, _) = authenticator_with_key(Role::Reader).await;
+
+        let token = issue_token(SECRET, "alice", Role::Admin, Duration::hours(1)).unwrap();
+        let principal = authenticator
+            .authenticate(&headers(header::AUTHORIZATION, &format!("Bearer {token}")))
+            .await
+            .unwrap();
+        assert_eq!(principal, Some(Principal::jwt("alice", Role::Admin)));
+
+        let expired = issue_token(SECRET, "alice", Role::Admin, Duration::hours(-1)).unwrap();
+        let forged = issue_token("other-secret", "alice", Role::Admin, Duration::hours(1)).unwrap();
+        for token in [expired, forged] {
+            let result = authenticator
+                .authenticate(&headers(header::AUTHORIZATION, &format!("Bearer {token}")))
+                .await;
+            assert!(matches!(result, Err(AuthError::InvalidToken(_))));
+        }
+
+        let without_secret = Authenticator::new(Arc::new(InMemoryAuthStore::new()), None);
+        let token = issue_token(SECRET, "alice", Role::Admin, Duration::hours(1)).unwrap();
+        let result = without_secret
+            .authenticate(&headers(header::AUTHORIZATION, &format!("Bearer {token}")))
+            .await;
+        assert!(matches!(result, Err(AuthError::JwtDisabled)));
+    }
+}
@@ -0,0 +1,140 @@
+//! APIキー管理用のコマンドラインツール
+//!
+//! 使用方法:
+//!   cargo run --bin api-keys -- create --name ci --role editor
+//!   cargo run --bin api-keys -- list
+//!   cargo run --bin api-keys -- revoke ci
+//!   cargo run --bin api-keys -- token --subject alice --role admin --hours 8
+//!
+//! キー本体は作成時に一度だけ表示され、データベースにはハッシュだけが保存されます。
+//! キーの作成・失効は監査ログに記録されます。
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use nba_trade_scraper::{
+    auth::{api_key_prefix, generate_api_key, hash_api_key, issue_token, Role},
+    db::{connection::create_pool, AuthRepository, AuthStore, NewAuditEntry},
+};
+
+#[derive(Parser)]
+#[command(name = "api-keys")]
+#[command(about = "GraphQL APIのAPIキーを管理")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// APIキーを作成して表示
+    Create {
+        /// キーの用途を表す一意な名前
+        #[arg(long)]
+        name: String,
+        /// ロール（reader、editor、admin）
+        #[arg(long)]
+        role: Role,
+    },
+    /// APIキーの一覧を表示
+    List,
+    /// APIキーを失効させる
+    Revoke {
+        /// キーの名前
+        name: String,
+    },
+    /// JWT_SECRETで署名したJWTを発行（データベースは使わない）
+    Token {
+        /// 利用者の識別子
+        #[arg(long)]
+        subject: String,
+        /// ロール（reader、editor、admin）
+        #[arg(long)]
+        role: Role,
+        /// 有効期間（時間）
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    if let Command::Token {
+        subject,
+        role,
+        hours,
+    } = &cli.command
+    {
+        let secret = std::env::var("JWT_SECRET").context("JWT_SECRET is not set")?;
+        let token = issue_token(&secret, subject, *role, chrono::Duration::hours(*hours))?;
+        println!("{token}");
+        return Ok(());
+    }
+
+    let pool = create_pool().await?;
+    let store = AuthStore::new(pool);
+    let actor = format!(
+        "cli:{}",
+        std::env::var("USER").unwrap_or_else(|_| "unknown".to_string())
+    );
+
+    match cli.command {
+        Command::Create { name, role } => {
+            let key = generate_api_key();
+            let result = store
+                .create_api_key(&name, role, &hash_api_key(&key), &api_key_prefix(&key))
+                .await;
+            record(&store, &actor, "createApiKey", &name, result.is_ok()).await?;
+            result?;
+
+            println!("Created API key {name} ({role}). It will not be shown again:");
+            println!("{key}");
+        }
+        Command::List => {
+            for key in store.list_api_keys().await? {
+                let status = match key.revoked_at {
+                    Some(revoked_at) => format!("revoked {}", revoked_at.to_rfc3339()),
+                    None => "active".to_string(),
+                };
+                let last_used = key
+                    .last_used_at
+                    .map_or_else(|| "never".to_string(), |at| at.to_rfc3339());
+                println!(
+                    "{}\t{}…\t{}\t{}\tlast used {}",
+                    key.name, key.key_prefix, key.role, status, last_used
+                );
+            }
+        }
+        Command::Revoke { name } => {
+            let revoked = store.revoke_api_key(&name).await?;
+            record(&store, &actor, "revokeApiKey", &name, revoked).await?;
+            if !revoked {
+                bail!("No active API key named {name}");
+            }
+            println!("Revoked API key {name}");
+        }
+        Command::Token { .. } => unreachable!("handled before connecting to the database"),
+    }
+
+    Ok(())
+}
+
+/// CLIでの操作を監査ログに記録
+async fn record(
+    store: &AuthStore,
+    actor: &str,
+    action: &str,
+    name: &str,
+    success: bool,
+) -> Result<()> {
+    store
+        .record_audit(NewAuditEntry {
+            actor: actor.to_string(),
+            role: Some(Role::Admin),
+            action: action.to_string(),
+            success,
+            details: Some(format!("name={name}")),
+        })
+        .await
+}
@@ -4,8 +4,8 @@
 
 use anyhow::Result;
 use nba_trade_scraper::{
-    db::connection::create_pool,
-    scraper::{NewsPersistence, RssParser},
+    db::{connection::create_pool, NewsRepository, NewsStore},
+    scraper::RssParser,
 };
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -29,20 +29,26 @@ async fn main() -> Result<()> {
     info!("Fetched {} items from RSS feeds", news_items.len());
 
     // データベースに保存
-    let persistence = NewsPersistence::new(pool);
-    let result = persistence.save_news_items(news_items).await?;
+    let store = NewsStore::new(pool);
+    let result = store.save_news_items(news_items).await?;
 
     info!(
-        "Initial scraping completed: {} saved, {} skipped, {} errors",
+        "Initial scraping completed: {} saved, {} updated, {} skipped, {} errors",
         result.saved_count,
+        result.updated_count,
         result.skipped_count,
         result.errors.len()
     );
 
     if !result.errors.is_empty() {
         info!("Errors occurred:");
-        for (id, error) in &result.errors {
-            info!("  - {}: {}", id, error);
+        for error in &result.errors {
+            info!(
+                "  - {} [{}]: {}",
+                error.item_id,
+                error.code.as_str(),
+                error.message
+            );
         }
     }
 
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use clap::Parser;
 use nba_trade_scraper::{
-    db::connection::create_pool,
-    scheduler::{create_scheduler, run_scraping_job},
+    db::JobKind,
+    db::{connection::create_pool, NewsStore, SharedNewsRepository},
+    jobs::JobRunner,
+    scheduler::{create_scheduler, run_scheduled_job, run_trending_job, SchedulerConfig},
+    shutdown::{shutdown_signal, BackgroundTasks, ShutdownConfig},
 };
-use tokio::signal;
-use tracing::{error, info, Level};
+use std::sync::Arc;
+use tracing::{error, info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
 #[derive(Parser)]
@@ -31,30 +34,44 @@ async fn main() -> Result<()> {
 
     // データベース接続（PostgreSQL）
     let pool = create_pool().await?;
+    let repository: SharedNewsRepository = Arc::new(NewsStore::new(pool));
 
     info!("Database connected");
 
+    // スクレイピング・翻訳はJobRunnerで実行し、終了時に実行中のジョブを待つ
+    let tasks = BackgroundTasks::new();
+    let runner = JobRunner::new(repository.clone()).with_tasks(tasks.clone());
+
     // 起動時に即座に実行するオプション
     if cli.immediate {
         info!("Running immediate scraping job...");
-        match run_scraping_job(pool.clone()).await {
-            Ok(_) => info!("Initial scraping completed successfully"),
-            Err(e) => error!("Initial scraping failed: {}", e),
+        run_scheduled_job(&runner, JobKind::Scrape).await;
+        if let Err(e) = run_trending_job(repository).await {
+            error!("Initial trending refresh failed: {}", e);
         }
     }
 
     // スケジューラー作成・開始
-    let mut scheduler = create_scheduler(pool).await?;
+    let mut scheduler = create_scheduler(runner, SchedulerConfig::from_env()).await?;
     scheduler.start().await?;
 
     info!("Scheduler started. Press Ctrl+C to stop.");
     info!("Jobs will run every 5 minutes at :00, :05, :10, :15, etc.");
+    info!("Trending rankings will be refreshed every 10 minutes at :02, :12, :22, etc.");
 
-    // Ctrl+Cを待つ
-    signal::ctrl_c().await?;
+    // SIGTERM・Ctrl+Cを待つ
+    shutdown_signal().await;
 
+    // 新しいジョブを起動しないようにしてから、実行中のジョブを期限まで待つ
+    let deadline = ShutdownConfig::from_env().deadline();
     info!("Shutting down scheduler...");
     scheduler.shutdown().await?;
+    if !tasks.drain(deadline).await {
+        warn!(
+            "{} running jobs did not finish before the shutdown deadline",
+            tasks.len()
+        );
+    }
     info!("Scheduler stopped successfully");
 
     Ok(())
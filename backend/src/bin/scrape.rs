@@ -1,6 +1,6 @@
 use nba_trade_scraper::{
-    db::connection::create_pool,
-    scraper::{NewsPersistence, RssParser},
+    db::{connection::create_pool, NewsRepository, NewsStore},
+    scraper::RssParser,
 };
 use tracing::{error, info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -25,20 +25,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Fetched {} news items", news_items.len());
 
     // データベースに保存
-    let persistence = NewsPersistence::new(pool);
-    let result = persistence.save_news_items(news_items).await?;
+    let store = NewsStore::new(pool);
+    let result = store.save_news_items(news_items).await?;
 
     info!(
-        "Scraping completed: {} saved, {} skipped, {} errors",
+        "Scraping completed: {} saved, {} updated, {} skipped, {} errors",
         result.saved_count,
+        result.updated_count,
         result.skipped_count,
         result.errors.len()
     );
 
     if !result.errors.is_empty() {
         error!("Errors encountered:");
-        for (id, error) in result.errors {
-            error!("  {}: {}", id, error);
+        for error in result.errors {
+            error!(
+                "  {} [{}]: {}",
+                error.item_id,
+                error.code.as_str(),
+                error.message
+            );
         }
     }
 
@@ -0,0 +1,98 @@
+//! ニュースの追加・翻訳イベントの配信
+//!
+//! PostgreSQLでは保存・翻訳と同じトランザクションで`NOTIFY`を送り、各サーバー
+//! インスタンスが`LISTEN`して自分のサブスクライバーに配信します
+//! （[`NewsStore::spawn_event_listener`](super::store::NewsStore::spawn_event_listener)）。
+//! どのインスタンスで保存されても、全インスタンスの購読者に同じイベントが届きます。
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::db::models::TradeNews;
+
+/// `NOTIFY`/`LISTEN`で使うチャンネル名
+pub const NEWS_EVENTS_CHANNEL: &str = "trade_news_events";
+
+/// 購読者ごとに溜められるイベント数（超えた分は古いものから捨てられる）
+const EVENT_BUFFER_SIZE: usize = 256;
+
+/// イベントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewsEventKind {
+    /// 新しい記事が保存された
+    Added,
+    /// 記事の翻訳が完了した
+    Translated,
+}
+
+/// 購読者に配信するイベント
+#[derive(Debug, Clone)]
+pub struct NewsEvent {
+    pub kind: NewsEventKind,
+    /// イベント発生後の記事の内容
+    pub news: TradeNews,
+    /// 記事の関連チームのID
+    pub team_ids: Vec<String>,
+}
+
+/// `NOTIFY`のペイロード（8000バイト制限があるため記事IDだけを送る）
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct NewsNotification {
+    pub(crate) kind: NewsEventKind,
+    pub(crate) id: String,
+}
+
+/// プロセス内の購読者へイベントを配る
+#[derive(Clone)]
+pub struct NewsEventHub {
+    sender: broadcast::Sender<NewsEvent>,
+}
+
+impl NewsEventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+
+    /// イベントの購読を開始
+    pub fn subscribe(&self) -> broadcast::Receiver<NewsEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 購読者がいるか
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// 全購読者にイベントを送る（購読者がいなければ何もしない）
+    pub fn publish(&self, event: NewsEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for NewsEventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_payload_format() {
+        let payload: NewsNotification =
+            serde_json::from_str(r#"{"kind": "translated", "id": "abc"}"#).unwrap();
+        assert_eq!(payload.kind, NewsEventKind::Translated);
+        assert_eq!(payload.id, "abc");
+
+        let json = serde_json::to_string(&NewsNotification {
+            kind: NewsEventKind::Added,
+            id: "xyz".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"kind":"added","id":"xyz"}"#);
+    }
+}
@@ -0,0 +1,138 @@
+//! RSSフィードごとの取得状態
+//!
+//! スクレイピングはフィードを1つ取得するたびに結果を[`record_feed_fetch`]で
+//! 記録します。ヘルスチェック（[`crate::health`]）は、最後に取得できた日時と
+//! 連続して失敗した回数から、フィードが止まっていないかを判定します。
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::db::repository::NewsRepository;
+use crate::errors::{error_code, ErrorCode};
+use crate::scraper::models::NewsItem;
+
+/// 1回のフィードの取得結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedFetch {
+    /// ニュースソース名（ESPN、RealGMなど）
+    pub source: String,
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    /// 取得できた記事の数（失敗したら0）
+    pub item_count: i32,
+    /// 失敗した理由
+    pub error: Option<(ErrorCode, String)>,
+}
+
+impl FeedFetch {
+    /// フィードの取得結果から作る
+    pub fn new(
+        source: impl Into<String>,
+        url: impl Into<String>,
+        result: &anyhow::Result<Vec<NewsItem>>,
+    ) -> Self {
+        let (item_count, error) = match result {
+            Ok(items) => (items.len() as i32, None),
+            Err(e) => (0, Some((error_code(e), e.to_string()))),
+        };
+        Self {
+            source: source.into(),
+            url: url.into(),
+            fetched_at: Utc::now(),
+            item_count,
+            error,
+        }
+    }
+}
+
+/// フィードの最新の取得状態
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedStatus {
+    pub source: String,
+    pub url: String,
+    pub last_attempt_at: DateTime<Utc>,
+    /// 最後に取得できた日時（一度もなければ`None`）
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// 連続して失敗した回数（最後の取得が成功なら0）
+    pub consecutive_failures: i32,
+    /// 最後に取得できた記事の数
+    pub last_item_count: i32,
+    /// 最後の取得が失敗した理由
+    pub last_error: Option<String>,
+    pub last_error_code: Option<ErrorCode>,
+}
+
+impl FeedStatus {
+    /// 前回の状態に取得結果を反映する
+    pub fn apply(previous: Option<&FeedStatus>, fetch: &FeedFetch) -> FeedStatus {
+        let (last_success_at, consecutive_failures, last_item_count) = match &fetch.error {
+            None => (Some(fetch.fetched_at), 0, fetch.item_count),
+            Some(_) => (
+                previous.and_then(|status| status.last_success_at),
+                previous.map_or(0, |status| status.consecutive_failures) + 1,
+                previous.map_or(0, |status| status.last_item_count),
+            ),
+        };
+
+        FeedStatus {
+            source: fetch.source.clone(),
+            url: fetch.url.clone(),
+            last_attempt_at: fetch.fetched_at,
+            last_success_at,
+            consecutive_failures,
+            last_item_count,
+            last_error: fetch.error.as_ref().map(|(_, message)| message.clone()),
+            last_error_code: fetch.error.as_ref().map(|(code, _)| *code),
+        }
+    }
+}
+
+/// フィードの取得結果を記録する（記録できなくてもスクレイピングは続ける）
+pub async fn record_feed_fetch(repository: &dyn NewsRepository, fetch: FeedFetch) {
+    if let Err(e) = repository.record_feed_fetch(&fetch).await {
+        warn!("Failed to record fetch status of {}: {}", fetch.source, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AppError;
+
+    #[test]
+    fn test_apply_counts_consecutive_failures() {
+        let success = FeedFetch::new("ESPN", "https://example.com/rss", &Ok(vec![]));
+        let status = FeedStatus::apply(
+            None,
+            &FeedFetch {
+                item_count: 12,
+                ..success.clone()
+            },
+        );
+        assert_eq!(status.last_success_at, Some(success.fetched_at));
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.last_item_count, 12);
+
+        let failure = FeedFetch::new(
+            "ESPN",
+            "https://example.com/rss",
+            &Err(AppError::FeedFetch("timed out".to_string()).into()),
+        );
+        let status = FeedStatus::apply(Some(&status), &failure);
+        let status = FeedStatus::apply(Some(&status), &failure);
+        assert_eq!(status.consecutive_failures, 2);
+        // 最後に取得できた日時と記事の数は残す
+        assert_eq!(status.last_success_at, Some(success.fetched_at));
+        assert_eq!(status.last_item_count, 12);
+        assert_eq!(status.last_error_code, Some(ErrorCode::FeedFetchFailed));
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("Failed to fetch feed: timed out")
+        );
+
+        let status = FeedStatus::apply(Some(&status), &success);
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.last_error, None);
+        assert_eq!(status.last_error_code, None);
+    }
+}
@@ -0,0 +1,194 @@
+//! スクレイピング・翻訳のバックグラウンドジョブの状態
+//!
+//! ジョブの実行は[`crate::jobs::JobRunner`]が行い、進捗を書き込むたびに
+//! リポジトリへ保存します。保存先を共有していれば、ジョブを登録したのとは別の
+//! インスタンスからも状態を参照できます。
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::errors::{ErrorCode, ItemError};
+
+/// 進捗が更新されなくなり、失敗にしたジョブの理由
+pub const STALE_JOB_FAILURE: &str = "Job stopped making progress";
+
+/// ジョブの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// RSSフィードのスクレイピング
+    Scrape,
+    /// 未翻訳の記事の翻訳
+    Translate,
+}
+
+impl JobKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Scrape => "scrape",
+            JobKind::Translate => "translate",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "scrape" => Some(JobKind::Scrape),
+            "translate" => Some(JobKind::Translate),
+            _ => None,
+        }
+    }
+}
+
+/// ジョブの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobStatus {
+    /// 登録済みで、まだ始まっていない
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(JobStatus::Queued),
+            "running" => Some(JobStatus::Running),
+            "succeeded" => Some(JobStatus::Succeeded),
+            "failed" => Some(JobStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// 終了した（これ以上状態が変わらない）か
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Succeeded | JobStatus::Failed)
+    }
+}
+
+/// ジョブの状態と進捗
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// 登録した主体（例: api_key:ci、jwt:alice）
+    pub requested_by: String,
+    /// 処理を終えた数（スクレイピングはフィード、翻訳は記事）
+    pub processed: i32,
+    /// 処理対象の数（まだ分からなければ`None`）
+    pub total: Option<i32>,
+    pub saved_count: i32,
+    pub updated_count: i32,
+    pub skipped_count: i32,
+    pub translated_count: i32,
+    /// フィード・記事ごとのエラー（ジョブは続行する）
+    pub errors: Vec<ItemError>,
+    /// ジョブ全体が失敗した理由
+    pub failure: Option<String>,
+    pub failure_code: Option<ErrorCode>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Job {
+    /// 登録したばかりのジョブ
+    pub fn new(kind: JobKind, requested_by: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            status: JobStatus::Queued,
+            requested_by: requested_by.to_string(),
+            processed: 0,
+            total: None,
+            saved_count: 0,
+            updated_count: 0,
+            skipped_count: 0,
+            translated_count: 0,
+            errors: Vec::new(),
+            failure: None,
+            failure_code: None,
+            created_at: now,
+            started_at: None,
+            finished_at: None,
+            updated_at: now,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let now = Utc::now();
+        self.status = JobStatus::Running;
+        self.started_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// 対象を1つ処理し終えた
+    pub fn advance(&mut self) {
+        self.processed += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// 成功または失敗で終える（`failure`がコードと理由の`Some`なら失敗）
+    pub fn finish(&mut self, failure: Option<(ErrorCode, String)>) {
+        let now = Utc::now();
+        self.status = if failure.is_some() {
+            JobStatus::Failed
+        } else {
+            JobStatus::Succeeded
+        };
+        self.failure_code = failure.as_ref().map(|(code, _)| *code);
+        self.failure = failure.map(|(_, message)| message);
+        self.finished_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// 進捗が更新されなくなった（実行していたインスタンスが止まった）ジョブを失敗にする
+    pub fn abandon(&mut self) {
+        self.finish(Some((ErrorCode::Internal, STALE_JOB_FAILURE.to_string())));
+    }
+
+    pub fn error_count(&self) -> i32 {
+        self.errors.len() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_lifecycle() {
+        let mut job = Job::new(JobKind::Translate, "jwt:alice");
+        assert_eq!(job.status, JobStatus::Queued);
+        assert!(Uuid::parse_str(&job.id).is_ok());
+
+        job.start();
+        assert_eq!(job.status, JobStatus::Running);
+        assert!(!job.status.is_finished());
+
+        job.finish(Some((
+            ErrorCode::TranslationFailed,
+            "AWS credentials are not configured".to_string(),
+        )));
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.failure_code, Some(ErrorCode::TranslationFailed));
+        assert!(job.status.is_finished());
+        assert!(job.finished_at.is_some());
+
+        for status in [JobStatus::Queued, JobStatus::Succeeded] {
+            assert_eq!(JobStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(JobKind::parse("scrape"), Some(JobKind::Scrape));
+    }
+}
@@ -0,0 +1,1126 @@
+//! メモリ上で動く[`NewsRepository`]の実装
+//!
+//! データベースなしでGraphQL APIを動かすテストやデモ用です。
+//! 保存・更新・リビジョン記録の振る舞いはPostgreSQL実装と揃えています。
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+use crate::db::events::{NewsEvent, NewsEventHub, NewsEventKind};
+use crate::db::feed_status::{FeedFetch, FeedStatus};
+use crate::db::jobs::{Job, JobKind};
+use crate::db::models::{Team, TradeNews, TradeNewsRevision};
+use crate::db::overrides::{
+    clear_changes, override_changes, CurrentValues, NewsOverride, OverrideChange, PendingChange,
+    StoredOverride,
+};
+use crate::db::related::RelatedDocument;
+use crate::db::repository::{
+    reject_invalid_items, BatchPlan, ExistingNews, NewsFilter, NewsPage, NewsRepository,
+    PoolStatus, SaveResult,
+};
+use crate::db::search::{
+    count_term_matches, highlight_snippet, search_terms, snippet_source, SearchHit, SearchLanguage,
+    SNIPPET_MAX_CHARS,
+};
+use crate::db::stats::{assemble_buckets, StatsBucket, StatsGroupBy, StatsQuery};
+use crate::db::trending::{MentionCount, TrendingKind, TrendingSnapshot, TrendingWindow};
+use crate::scraper::models::NewsItem;
+use crate::scraper::players::{detect_player_ids, NBA_PLAYERS};
+use crate::scraper::teams::{detect_team_ids, NBA_TEAMS};
+
+#[derive(Default)]
+struct MemoryState {
+    news: Vec<TradeNews>,
+    revisions: Vec<TradeNewsRevision>,
+    teams: Vec<Team>,
+    /// 記事IDごとの関連チームのID
+    news_teams: HashMap<String, Vec<String>>,
+    /// 記事IDごとの関連選手のID
+    news_players: HashMap<String, Vec<String>>,
+    /// 集計期間ごとの最新のトレンドのランキング
+    trending: HashMap<TrendingWindow, TrendingSnapshot>,
+    /// 記事IDごとの編集者による上書き
+    overrides: HashMap<String, StoredOverride>,
+    /// 上書きの変更履歴（古い順）
+    override_log: Vec<OverrideChange>,
+    /// IDごとのバックグラウンドジョブ
+    jobs: HashMap<String, Job>,
+    /// SHA-256ハッシュごとの永続化クエリ
+    persisted_queries: HashMap<String, String>,
+    /// ソース名ごとのフィードの取得状態
+    feed_statuses: HashMap<String, FeedStatus>,
+}
+
+impl MemoryState {
+    fn team_ids(&self, news_id: &str) -> &[String] {
+        self.news_teams
+            .get(news_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn player_ids(&self, news_id: &str) -> &[String] {
+        self.news_players
+            .get(news_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn is_hidden(&self, news_id: &str) -> bool {
+        self.overrides
+            .get(news_id)
+            .and_then(|stored| stored.values.hidden)
+            .unwrap_or(false)
+    }
+
+    /// 非表示にされていない記事
+    fn visible_news(&self) -> impl Iterator<Item = &TradeNews> {
+        self.news.iter().filter(|item| !self.is_hidden(&item.id))
+    }
+
+    fn related_document(&self, item: &TradeNews) -> RelatedDocument {
+        RelatedDocument {
+            id: item.id.clone(),
+            title: item.title.clone(),
+            description: item.description.clone(),
+            published_at: item.published_at,
+            team_ids: self.team_ids(&item.id).to_vec(),
+            player_ids: self.player_ids(&item.id).to_vec(),
+        }
+    }
+
+    fn current_values(&self, news_id: &str) -> Option<CurrentValues> {
+        let row = self.news.iter().find(|row| row.id == news_id)?;
+        Some(CurrentValues {
+            category: row.category.clone(),
+            team_ids: self.team_ids(news_id).to_vec(),
+            player_ids: self.player_ids(news_id).to_vec(),
+            is_official: row.is_official,
+            hidden: self.is_hidden(news_id),
+        })
+    }
+
+    /// 編集者の上書きを記事と関連チーム・選手に反映する
+    fn apply_override(&mut self, news_id: &str) {
+        let Some(values) = self
+            .overrides
+            .get(news_id)
+            .map(|stored| stored.values.clone())
+        else {
+            return;
+        };
+        if let Some(row) = self.news.iter_mut().find(|row| row.id == news_id) {
+            if let Some(category) = values.category {
+                row.category = category;
+            }
+            if let Some(is_official) = values.is_official {
+                row.is_official = is_official;
+            }
+        }
+        if let Some(team_ids) = values.team_ids {
+            self.news_teams.insert(news_id.to_string(), team_ids);
+        }
+        if let Some(player_ids) = values.player_ids {
+            self.news_players.insert(news_id.to_string(), player_ids);
+        }
+    }
+
+    fn record_override_changes(
+        &mut self,
+        news_id: &str,
+        changes: Vec<PendingChange>,
+        editor: &str,
+    ) {
+        let now = Utc::now();
+        for (field, previous_value, new_value) in changes {
+            let id = self.override_log.len() as i64 + 1;
+            self.override_log.push(OverrideChange {
+                id,
+                news_id: news_id.to_string(),
+                field,
+                previous_value,
+                new_value,
+                editor: editor.to_string(),
+                changed_at: now,
+            });
+        }
+    }
+}
+
+/// メモリ上に保存するニュースとチームのデータアクセス
+pub struct InMemoryNewsStore {
+    state: RwLock<MemoryState>,
+    events: NewsEventHub,
+}
+
+impl InMemoryNewsStore {
+    /// NBA全30チームを登録した空のストアを作成
+    pub fn new() -> Self {
+        let now = Utc::now();
+        let teams = NBA_TEAMS
+            .iter()
+            .map(|(id, name, name_ja, city, conference, division)| Team {
+                id: id.to_string(),
+                name: name.to_string(),
+                name_ja: Some(name_ja.to_string()),
+                abbreviation: id.to_string(),
+                city: city.to_string(),
+                conference: conference.to_string(),
+                division: division.to_string(),
+                created_at: Some(now),
+                updated_at: Some(now),
+            })
+            .collect();
+
+        Self {
+            state: RwLock::new(MemoryState {
+                teams,
+                ..MemoryState::default()
+            }),
+            events: NewsEventHub::new(),
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, MemoryState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, MemoryState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 条件に合うニュースを公開日時の新しい順に返す
+    ///
+    /// `filter`には記事と関連チームのIDが渡されます。
+    fn select_news(&self, filter: impl Fn(&TradeNews, &[String]) -> bool) -> Vec<TradeNews> {
+        let state = self.read();
+        let mut items: Vec<TradeNews> = state
+            .visible_news()
+            .filter(|item| filter(item, state.team_ids(&item.id)))
+            .cloned()
+            .collect();
+        items.sort_by_key(|item| Reverse(item.published_at));
+        items
+    }
+}
+
+impl Default for InMemoryNewsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn new_row(item: NewsItem, now: DateTime<Utc>) -> TradeNews {
+    let content_hash = item.content_hash();
+    TradeNews {
+        id: item.id,
+        title: item.title,
+        description: item.description,
+        source: item.source.to_string(),
+        link: item.link,
+        author: None,
+        category: item.category,
+        team_id: None,
+        is_official: false,
+        published_at: item.published_at,
+        scraped_at: Some(now),
+        title_ja: None,
+        description_ja: None,
+        translation_status: "pending".to_string(),
+        translated_at: None,
+        image_url: item.image_url,
+        thumbnail_url: item.thumbnail_url,
+        content_hash: Some(content_hash),
+        revision: 1,
+        created_at: Some(now),
+        updated_at: Some(now),
+    }
+}
+
+#[async_trait]
+impl NewsRepository for InMemoryNewsStore {
+    async fn save_news_items(&self, items: Vec<NewsItem>) -> Result<SaveResult> {
+        let (valid_items, mut result) = reject_invalid_items(items);
+        let now = Utc::now();
+        let mut state = self.write();
+
+        let existing: Vec<ExistingNews> = state
+            .news
+            .iter()
+            .filter(|row| {
+                valid_items
+                    .iter()
+                    .any(|item| item.id == row.id || item.link == row.link)
+            })
+            .map(|row| ExistingNews {
+                id: row.id.clone(),
+                link: row.link.clone(),
+                content_hash: row.content_hash.clone(),
+            })
+            .collect();
+
+        let plan = BatchPlan::build(valid_items, &existing);
+        result.count_plan(&plan, |_| true);
+
+        for update in plan.updates {
+            let next_id = state.revisions.len() as i64 + 1;
+            let Some(row) = state
+                .news
+                .iter_mut()
+                .find(|row| row.id == update.existing_id)
+            else {
+                continue;
+            };
+
+            let revision = TradeNewsRevision {
+                id: next_id,
+                news_id: row.id.clone(),
+                revision: row.revision,
+                title: row.title.clone(),
+                description: row.description.clone(),
+                category: row.category.clone(),
+                title_ja: row.title_ja.clone(),
+                description_ja: row.description_ja.clone(),
+                content_hash: row.content_hash.clone(),
+                recorded_at: now,
+            };
+
+            let item = update.item;
+            row.category = NewsItem::determine_category(&item.title, item.description.as_deref());
+            row.content_hash = Some(item.content_hash());
+            row.title = item.title;
+            row.description = item.description;
+            row.image_url = item.image_url.or(row.image_url.take());
+            row.thumbnail_url = item.thumbnail_url.or(row.thumbnail_url.take());
+            row.revision += 1;
+            row.scraped_at = Some(now);
+            row.title_ja = None;
+            row.description_ja = None;
+            row.translation_status = "pending".to_string();
+            row.translated_at = None;
+            row.updated_at = Some(now);
+
+            let team_ids = detect_team_ids(&row.title, row.description.as_deref());
+            let player_ids = detect_player_ids(&row.title, row.description.as_deref());
+            let news_id = row.id.clone();
+            state.news_players.insert(news_id.clone(), player_ids);
+            state.news_teams.insert(news_id.clone(), team_ids);
+            state.revisions.push(revision);
+            // 分類し直しても編集者の上書きは残す
+            state.apply_override(&news_id);
+        }
+
+        for item in plan.inserts {
+            let row = new_row(item, now);
+            let team_ids = detect_team_ids(&row.title, row.description.as_deref());
+            self.events.publish(NewsEvent {
+                kind: NewsEventKind::Added,
+                news: row.clone(),
+                team_ids: team_ids.clone(),
+            });
+            state.news_teams.insert(row.id.clone(), team_ids);
+            state.news_players.insert(
+                row.id.clone(),
+                detect_player_ids(&row.title, row.description.as_deref()),
+            );
+            state.news.push(row);
+        }
+
+        Ok(result)
+    }
+
+    async fn get_news(&self, id: &str) -> Result<Option<TradeNews>> {
+        Ok(self.read().visible_news().find(|row| row.id == id).cloned())
+    }
+
+    async fn get_news_including_hidden(&self, id: &str) -> Result<Option<TradeNews>> {
+        Ok(self.read().news.iter().find(|row| row.id == id).cloned())
+    }
+
+    async fn exists_by_external_id(&self, external_id: &str) -> Result<bool> {
+        Ok(self.read().news.iter().any(|item| item.id == external_id))
+    }
+
+    async fn get_recent_news(&self, limit: i64) -> Result<Vec<TradeNews>> {
+        let mut items = self.select_news(|_, _| true);
+        items.truncate(limit.max(0) as usize);
+        Ok(items)
+    }
+
+    async fn get_news_since(&self, since: DateTime<Utc>) -> Result<Vec<TradeNews>> {
+        Ok(self.select_news(|item, _| item.published_at > since))
+    }
+
+    async fn get_news_by_category(&self, category: &str) -> Result<Vec<TradeNews>> {
+        Ok(self.select_news(|item, _| item.category == category))
+    }
+
+    async fn get_news_by_source(&self, source: &str) -> Result<Vec<TradeNews>> {
+        Ok(self.select_news(|item, _| item.source.eq_ignore_ascii_case(source)))
+    }
+
+    async fn get_pending_translations(&self, limit: i64) -> Result<Vec<TradeNews>> {
+        let mut items = self.select_news(|item, _| item.translation_status == "pending");
+        items.truncate(limit.max(0) as usize);
+        Ok(items)
+    }
+
+    async fn save_translation(
+        &self,
+        id: &str,
+        title_ja: &str,
+        description_ja: Option<&str>,
+    ) -> Result<()> {
+        let mut state = self.write();
+        let team_ids = state.team_ids(id).to_vec();
+        if let Some(row) = state.news.iter_mut().find(|row| row.id == id) {
+            row.title_ja = Some(title_ja.to_string());
+            row.description_ja = description_ja.map(str::to_string);
+            row.translation_status = "completed".to_string();
+            row.translated_at = Some(Utc::now());
+            self.events.publish(NewsEvent {
+                kind: NewsEventKind::Translated,
+                news: row.clone(),
+                team_ids,
+            });
+        }
+        Ok(())
+    }
+
+    async fn get_revisions(&self, news_id: &str) -> Result<Vec<TradeNewsRevision>> {
+        let mut revisions: Vec<TradeNewsRevision> = self
+            .read()
+            .revisions
+            .iter()
+            .filter(|revision| revision.news_id == news_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|revision| Reverse(revision.revision));
+        Ok(revisions)
+    }
+
+    async fn get_teams(&self) -> Result<Vec<Team>> {
+        let mut teams = self.read().teams.clone();
+        teams.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(teams)
+    }
+
+    async fn get_team(&self, id: &str) -> Result<Option<Team>> {
+        Ok(self.read().teams.iter().find(|team| team.id == id).cloned())
+    }
+
+    async fn get_teams_for_news(&self, news_ids: &[String]) -> Result<HashMap<String, Vec<Team>>> {
+        let state = self.read();
+        let mut result = HashMap::new();
+        for news_id in news_ids {
+            let teams: Vec<Team> = state
+                .team_ids(news_id)
+                .iter()
+                .filter_map(|team_id| state.teams.iter().find(|team| &team.id == team_id))
+                .cloned()
+                .collect();
+            if !teams.is_empty() {
+                result.insert(news_id.clone(), teams);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn count_news(&self) -> Result<i64> {
+        Ok(self.read().news.len() as i64)
+    }
+
+    async fn last_modified(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .read()
+            .news
+            .iter()
+            .flat_map(|item| [item.scraped_at, item.translated_at])
+            .flatten()
+            .max())
+    }
+
+    async fn list_news(&self, filter: &NewsFilter, page: &NewsPage) -> Result<Vec<TradeNews>> {
+        let mut items = self.select_news(|item, team_ids| {
+            let key = (item.published_at, item.id.as_str());
+            filter.matches(item, team_ids)
+                && page.after.as_ref().map_or(true, |cursor| {
+                    key < (cursor.published_at, cursor.id.as_str())
+                })
+                && page.before.as_ref().map_or(true, |cursor| {
+                    key > (cursor.published_at, cursor.id.as_str())
+                })
+        });
+        items.sort_by(|a, b| (b.published_at, &b.id).cmp(&(a.published_at, &a.id)));
+
+        let limit = page.limit.max(0) as usize;
+        if page.from_end {
+            items.drain(..items.len().saturating_sub(limit));
+        } else {
+            items.truncate(limit);
+        }
+        Ok(items)
+    }
+
+    async fn count_matching_news(&self, filter: &NewsFilter) -> Result<i64> {
+        let state = self.read();
+        Ok(state
+            .visible_news()
+            .filter(|item| filter.matches(item, state.team_ids(&item.id)))
+            .count() as i64)
+    }
+
+    fn subscribe_events(&self) -> broadcast::Receiver<NewsEvent> {
+        self.events.subscribe()
+    }
+
+    async fn search_news(
+        &self,
+        query: &str,
+        language: SearchLanguage,
+        filter: &NewsFilter,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 語の出現回数を関連度とし、タイトルでの一致は2倍に数える
+        let mut hits: Vec<SearchHit> = self
+            .select_news(|item, team_ids| filter.matches(item, team_ids))
+            .into_iter()
+            .filter_map(|news| {
+                let (title, description) = match language {
+                    SearchLanguage::English => (news.title.clone(), news.description.clone()),
+                    SearchLanguage::Japanese => (
+                        news.title_ja.clone().unwrap_or_default(),
+                        news.description_ja.clone(),
+                    ),
+                };
+                let text = format!("{} {}", title, description.as_deref().unwrap_or_default());
+                let matches = count_term_matches(&text, &terms)?;
+                let title_matches = count_term_matches(&title, &terms).unwrap_or(0);
+                let source = snippet_source(&title, description.as_deref(), &terms);
+
+                Some(SearchHit {
+                    snippet: highlight_snippet(source, &terms, SNIPPET_MAX_CHARS),
+                    rank: (matches + title_matches) as f32,
+                    news,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+        hits.truncate(limit.max(0) as usize);
+        Ok(hits)
+    }
+
+    async fn news_stats(&self, query: &StatsQuery) -> Result<Vec<StatsBucket>> {
+        let state = self.read();
+        let mut totals: HashMap<DateTime<Utc>, i64> = HashMap::new();
+        let mut groups: HashMap<(DateTime<Utc>, String), i64> = HashMap::new();
+
+        for item in state
+            .visible_news()
+            .filter(|item| item.published_at >= query.from && item.published_at < query.to)
+        {
+            let start = query.bucket_start(item.published_at);
+            *totals.entry(start).or_default() += 1;
+
+            let keys = match query.group_by {
+                Some(StatsGroupBy::Category) => vec![item.category.clone()],
+                Some(StatsGroupBy::Source) => vec![item.source.clone()],
+                Some(StatsGroupBy::TranslationStatus) => vec![item.translation_status.clone()],
+                Some(StatsGroupBy::Team) => state.team_ids(&item.id).to_vec(),
+                None => Vec::new(),
+            };
+            for key in keys {
+                *groups.entry((start, key)).or_default() += 1;
+            }
+        }
+
+        Ok(assemble_buckets(
+            query,
+            totals,
+            groups
+                .into_iter()
+                .map(|((start, key), count)| (start, key, count)),
+        ))
+    }
+
+    async fn override_news(
+        &self,
+        news_id: &str,
+        patch: &NewsOverride,
+        editor: &str,
+    ) -> Result<Option<TradeNews>> {
+        let mut state = self.write();
+        let Some(current) = state.current_values(news_id) else {
+            return Ok(None);
+        };
+
+        let stored = state
+            .overrides
+            .entry(news_id.to_string())
+            .or_insert_with(|| StoredOverride {
+                news_id: news_id.to_string(),
+                values: NewsOverride::default(),
+                updated_by: editor.to_string(),
+                updated_at: Utc::now(),
+            });
+        stored.values.merge(patch);
+        stored.updated_by = editor.to_string();
+        stored.updated_at = Utc::now();
+
+        state.record_override_changes(news_id, override_changes(&current, patch), editor);
+        state.apply_override(news_id);
+
+        Ok(state.news.iter().find(|row| row.id == news_id).cloned())
+    }
+
+    async fn clear_news_override(&self, news_id: &str, editor: &str) -> Result<Option<TradeNews>> {
+        let mut state = self.write();
+        let Some(current) = state.current_values(news_id) else {
+            return Ok(None);
+        };
+
+        if let Some(stored) = state.overrides.remove(news_id) {
+            state.record_override_changes(news_id, clear_changes(&current, &stored.values), editor);
+
+            // 分類器の出力に戻す
+            if let Some(row) = state.news.iter_mut().find(|row| row.id == news_id) {
+                let predicted = CurrentValues::predicted(&row.title, row.description.as_deref());
+                row.category = predicted.category;
+                row.is_official = predicted.is_official;
+                state
+                    .news_teams
+                    .insert(news_id.to_string(), predicted.team_ids);
+                state
+                    .news_players
+                    .insert(news_id.to_string(), predicted.player_ids);
+            }
+        }
+
+        Ok(state.news.iter().find(|row| row.id == news_id).cloned())
+    }
+
+    async fn override_history(
+        &self,
+        news_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<OverrideChange>> {
+        Ok(self
+            .read()
+            .override_log
+            .iter()
+            .rev()
+            .filter(|change| news_id.map_or(true, |id| change.news_id == id))
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_overrides(&self, limit: i64) -> Result<Vec<(TradeNews, StoredOverride)>> {
+        let state = self.read();
+        let mut overrides: Vec<&StoredOverride> = state.overrides.values().collect();
+        overrides.sort_by(|a, b| {
+            b.updated_at
+                .cmp(&a.updated_at)
+                .then_with(|| a.news_id.cmp(&b.news_id))
+        });
+
+        Ok(overrides
+            .into_iter()
+            .filter_map(|stored| {
+                let news = state.news.iter().find(|row| row.id == stored.news_id)?;
+                Some((news.clone(), stored.clone()))
+            })
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn mention_counts(
+        &self,
+        baseline_start: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<MentionCount>> {
+        let state = self.read();
+        let mut counts: HashMap<(TrendingKind, String, String), MentionCount> = HashMap::new();
+
+        for item in state
+            .visible_news()
+            .filter(|item| item.published_at >= baseline_start && item.published_at < end)
+        {
+            let in_window = item.published_at >= window_start;
+            let teams = state.team_ids(&item.id).iter().filter_map(|id| {
+                let team = state.teams.iter().find(|team| &team.id == id)?;
+                Some((
+                    TrendingKind::Team,
+                    id,
+                    format!("{} {}", team.city, team.name),
+                    team.name_ja.clone(),
+                ))
+            });
+            let players = state.player_ids(&item.id).iter().filter_map(|id| {
+                let (_, name, name_ja) = NBA_PLAYERS.iter().find(|(pid, ..)| pid == id)?;
+                Some((
+                    TrendingKind::Player,
+                    id,
+                    name.to_string(),
+                    Some(name_ja.to_string()),
+                ))
+            });
+
+            for (kind, id, name, name_ja) in teams.chain(players) {
+                let count = counts
+                    .entry((kind, id.clone(), item.category.clone()))
+                    .or_insert_with(|| MentionCount {
+                        kind,
+                        id: id.clone(),
+                        name,
+                        name_ja,
+                        category: item.category.clone(),
+                        in_window: 0,
+                        in_baseline: 0,
+                    });
+                if in_window {
+                    count.in_window += 1;
+                } else {
+                    count.in_baseline += 1;
+                }
+            }
+        }
+
+        Ok(counts.into_values().collect())
+    }
+
+    async fn related_corpus(&self, limit: i64) -> Result<Vec<RelatedDocument>> {
+        let state = self.read();
+        let mut news: Vec<&TradeNews> = state.visible_news().collect();
+        news.sort_by(|a, b| {
+            b.published_at
+                .cmp(&a.published_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        Ok(news
+            .into_iter()
+            .take(limit.max(0) as usize)
+            .map(|item| state.related_document(item))
+            .collect())
+    }
+
+    async fn related_document(&self, id: &str) -> Result<Option<RelatedDocument>> {
+        let state = self.read();
+        let document = state
+            .visible_news()
+            .find(|item| item.id == id)
+            .map(|item| state.related_document(item));
+        Ok(document)
+    }
+
+    async fn save_trending(&self, snapshot: &TrendingSnapshot) -> Result<()> {
+        self.write()
+            .trending
+            .insert(snapshot.window, snapshot.clone());
+        Ok(())
+    }
+
+    async fn get_trending(&self, window: TrendingWindow) -> Result<Option<TrendingSnapshot>> {
+        Ok(self.read().trending.get(&window).cloned())
+    }
+
+    async fn create_job(&self, job: &Job, updated_since: DateTime<Utc>) -> Result<Option<Job>> {
+        let mut state = self.write();
+        let mut active = None;
+        for existing in state.jobs.values_mut() {
+            if existing.kind != job.kind || existing.status.is_finished() {
+                continue;
+            }
+            if existing.updated_at < updated_since {
+                existing.abandon();
+            } else {
+                active = Some(existing.clone());
+            }
+        }
+        if active.is_none() {
+            state.jobs.insert(job.id.clone(), job.clone());
+        }
+        Ok(active)
+    }
+
+    async fn save_job(&self, job: &Job) -> Result<()> {
+        self.write().jobs.insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn get_job(&self, id: &str) -> Result<Option<Job>> {
+        Ok(self.read().jobs.get(id).cloned())
+    }
+
+    async fn active_job(&self, kind: JobKind, updated_since: DateTime<Utc>) -> Result<Option<Job>> {
+        Ok(self
+            .read()
+            .jobs
+            .values()
+            .filter(|job| job.kind == kind && !job.status.is_finished())
+            .filter(|job| job.updated_at >= updated_since)
+            .min_by_key(|job| job.created_at)
+            .cloned())
+    }
+
+    async fn get_persisted_query(&self, hash: &str) -> Result<Option<String>> {
+        Ok(self.read().persisted_queries.get(hash).cloned())
+    }
+
+    async fn save_persisted_query(&self, hash: &str, query: &str) -> Result<()> {
+        self.write()
+            .persisted_queries
+            .entry(hash.to_string())
+            .or_insert_with(|| query.to_string());
+        Ok(())
+    }
+
+    async fn record_feed_fetch(&self, fetch: &FeedFetch) -> Result<()> {
+        let mut state = self.write();
+        let status = FeedStatus::apply(state.feed_statuses.get(&fetch.source), fetch);
+        state.feed_statuses.insert(fetch.source.clone(), status);
+        Ok(())
+    }
+
+    async fn feed_statuses(&self) -> Result<Vec<FeedStatus>> {
+        let mut statuses: Vec<FeedStatus> = self.read().feed_statuses.values().cloned().collect();
+        statuses.sort_by(|a, b| a.source.cmp(&b.source));
+        Ok(statuses)
+    }
+
+    async fn pending_migrations(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn pool_status(&self) -> Option<PoolStatus> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::repository::NewsCursor;
+    use crate::db::test_support::news_item;
+    use crate::scraper::NewsSource;
+
+    #[tokio::test]
+    async fn test_save_and_skip_unchanged_items() {
+        let store = InMemoryNewsStore::new();
+
+        let result = store
+            .save_news_items(vec![news_item("a", "First"), news_item("b", "Second")])
+            .await
+            .unwrap();
+        assert_eq!(result.saved_count, 2);
+
+        let result = store
+            .save_news_items(vec![news_item("a", "First")])
+            .await
+            .unwrap();
+        assert_eq!(result.saved_count, 0);
+        assert_eq!(result.skipped_count, 1);
+        assert_eq!(store.count_news().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_changed_item_records_revision() {
+        let store = InMemoryNewsStore::new();
+        store
+            .save_news_items(vec![news_item("a", "Lakers nearing deal")])
+            .await
+            .unwrap();
+        store.save_translation("a", "合意間近", None).await.unwrap();
+
+        let result = store
+            .save_news_items(vec![news_item("a", "Lakers sign veteran guard")])
+            .await
+            .unwrap();
+        assert_eq!(result.updated_count, 1);
+
+        let saved = store.get_recent_news(10).await.unwrap();
+        assert_eq!(saved[0].title, "Lakers sign veteran guard");
+        assert_eq!(saved[0].category, "Signing");
+        assert_eq!(saved[0].revision, 2);
+        assert_eq!(saved[0].translation_status, "pending");
+        assert_eq!(saved[0].title_ja, None);
+
+        let revisions = store.get_revisions("a").await.unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].title, "Lakers nearing deal");
+        assert_eq!(revisions[0].title_ja.as_deref(), Some("合意間近"));
+    }
+
+    #[tokio::test]
+    async fn test_filters_and_ordering() {
+        let store = InMemoryNewsStore::new();
+        let mut older = news_item("old", "Celtics trade rumor");
+        older.published_at = Utc::now() - chrono::Duration::days(2);
+        let mut newer = news_item("new", "Heat sign forward");
+        newer.source = NewsSource::RealGM;
+        store.save_news_items(vec![older, newer]).await.unwrap();
+
+        let recent = store.get_recent_news(10).await.unwrap();
+        assert_eq!(recent[0].id, "new");
+        assert_eq!(store.get_recent_news(1).await.unwrap().len(), 1);
+
+        let trades = store.get_news_by_category("Trade").await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].id, "old");
+
+        let realgm = store.get_news_by_source("realgm").await.unwrap();
+        assert_eq!(realgm.len(), 1);
+
+        let since = store
+            .get_news_since(Utc::now() - chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(since.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pending_translations() {
+        let store = InMemoryNewsStore::new();
+        store
+            .save_news_items(vec![news_item("a", "First")])
+            .await
+            .unwrap();
+        assert_eq!(store.get_pending_translations(10).await.unwrap().len(), 1);
+
+        store
+            .save_translation("a", "最初", Some("説明"))
+            .await
+            .unwrap();
+        assert!(store.get_pending_translations(10).await.unwrap().is_empty());
+
+        let saved = store.get_recent_news(1).await.unwrap();
+        assert_eq!(saved[0].translation_status, "completed");
+        assert_eq!(saved[0].description_ja.as_deref(), Some("説明"));
+    }
+
+    #[tokio::test]
+    async fn test_teams() {
+        let store = InMemoryNewsStore::new();
+        assert_eq!(store.get_teams().await.unwrap().len(), 30);
+
+        let lakers = store.get_team("LAL").await.unwrap().unwrap();
+        assert_eq!(lakers.name, "Lakers");
+        assert!(store.get_team("XXX").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_detects_teams_and_filters_by_team() {
+        let store = InMemoryNewsStore::new();
+        store
+            .save_news_items(vec![
+                news_item("trade", "Mavericks trade Luka Doncic to Lakers"),
+                news_item("other", "Celtics re-sign guard"),
+            ])
+            .await
+            .unwrap();
+
+        let teams = store
+            .get_teams_for_news(&["trade".to_string(), "other".to_string(), "x".to_string()])
+            .await
+            .unwrap();
+        let ids: Vec<&str> = teams["trade"].iter().map(|team| team.id.as_str()).collect();
+        assert_eq!(ids, vec!["DAL", "LAL"]);
+        assert_eq!(teams["other"][0].id, "BOS");
+        assert!(!teams.contains_key("x"));
+
+        let lakers = NewsFilter {
+            team_id: Some("LAL".to_string()),
+            ..NewsFilter::default()
+        };
+        assert_eq!(store.count_matching_news(&lakers).await.unwrap(), 1);
+
+        // 内容が変わればチームも判定し直す
+        let mut changed = news_item("trade", "Mavericks keep Luka Doncic");
+        changed.link = "https://example.com/trade".to_string();
+        store.save_news_items(vec![changed]).await.unwrap();
+        assert_eq!(store.count_matching_news(&lakers).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_news_ranks_and_filters() {
+        let store = InMemoryNewsStore::new();
+        let mut title_match = news_item("title", "Luka Doncic traded to Lakers");
+        title_match.published_at = Utc::now() - chrono::Duration::days(1);
+        let mut description_match = news_item("desc", "Blockbuster deal");
+        description_match.description = Some("Dallas sends <b>Luka</b> west".to_string());
+        let other = news_item("other", "Heat sign forward");
+        store
+            .save_news_items(vec![title_match, description_match, other])
+            .await
+            .unwrap();
+        store
+            .save_translation("desc", "大型トレード", Some("ダラスがルカを放出"))
+            .await
+            .unwrap();
+
+        let hits = store
+            .search_news("luka", SearchLanguage::English, &NewsFilter::default(), 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = hits.iter().map(|hit| hit.news.id.as_str()).collect();
+        assert_eq!(ids, vec!["title", "desc"]);
+        assert_eq!(hits[1].snippet, "Dallas sends <mark>Luka</mark> west");
+
+        let filter = NewsFilter {
+            published_after: Some(Utc::now() - chrono::Duration::hours(1)),
+            ..NewsFilter::default()
+        };
+        let hits = store
+            .search_news("luka", SearchLanguage::English, &filter, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].news.id, "desc");
+
+        let hits = store
+            .search_news("ルカ", SearchLanguage::Japanese, &NewsFilter::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "ダラスが<mark>ルカ</mark>を放出");
+
+        let hits = store
+            .search_news("  ", SearchLanguage::English, &NewsFilter::default(), 10)
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_news_keyset_pages() {
+        let store = InMemoryNewsStore::new();
+        let published_at = Utc::now();
+        // 同じ公開日時の記事はIDの降順で並ぶ
+        let items: Vec<NewsItem> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|id| {
+                let mut item = news_item(id, "Trade rumor");
+                item.published_at = published_at;
+                item
+            })
+            .collect();
+        store.save_news_items(items).await.unwrap();
+
+        let ids =
+            |items: &[TradeNews]| items.iter().map(|item| item.id.clone()).collect::<Vec<_>>();
+        let first = store
+            .list_news(
+                &NewsFilter::default(),
+                &NewsPage {
+                    limit: 2,
+                    ..NewsPage::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(&first), vec!["d", "c"]);
+
+        let next = store
+            .list_news(
+                &NewsFilter::default(),
+                &NewsPage {
+                    after: Some(NewsCursor::of(&first[1])),
+                    limit: 2,
+                    ..NewsPage::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(&next), vec!["b", "a"]);
+
+        let last = store
+            .list_news(
+                &NewsFilter::default(),
+                &NewsPage {
+                    before: Some(NewsCursor::of(&next[1])),
+                    limit: 2,
+                    from_end: true,
+                    ..NewsPage::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(&last), vec!["c", "b"]);
+
+        let filter = NewsFilter {
+            translation_status: Some("completed".to_string()),
+            ..NewsFilter::default()
+        };
+        assert_eq!(store.count_matching_news(&filter).await.unwrap(), 0);
+        assert_eq!(
+            store
+                .count_matching_news(&NewsFilter::default())
+                .await
+                .unwrap(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publishes_added_and_translated_events() {
+        let store = InMemoryNewsStore::new();
+        let mut events = store.subscribe_events();
+
+        store
+            .save_news_items(vec![news_item("a", "Lakers trade")])
+            .await
+            .unwrap();
+        // 変更のない再保存ではイベントを送らない
+        store
+            .save_news_items(vec![news_item("a", "Lakers trade")])
+            .await
+            .unwrap();
+        store
+            .save_translation("a", "レイカーズのトレード", None)
+            .await
+            .unwrap();
+
+        let added = events.recv().await.unwrap();
+        assert_eq!(added.kind, NewsEventKind::Added);
+        assert_eq!(added.news.id, "a");
+
+        let translated = events.recv().await.unwrap();
+        assert_eq!(translated.kind, NewsEventKind::Translated);
+        assert_eq!(
+            translated.news.title_ja.as_deref(),
+            Some("レイカーズのトレード")
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hidden_news_is_only_returned_to_editors() {
+        let store = InMemoryNewsStore::new();
+        store
+            .save_news_items(vec![news_item("a", "Lakers trade")])
+            .await
+            .unwrap();
+        let hide = NewsOverride {
+            hidden: Some(true),
+            ..NewsOverride::default()
+        };
+        let news = store.override_news("a", &hide, "jwt:editor").await.unwrap();
+        assert_eq!(news.unwrap().id, "a");
+
+        assert!(store.get_news("a").await.unwrap().is_none());
+        let news = store.get_news_including_hidden("a").await.unwrap();
+        assert_eq!(news.unwrap().id, "a");
+    }
+}
@@ -3,9 +3,40 @@
 //! このモジュールは、PostgreSQLデータベースへの接続プールの作成と
 //! マイグレーションの実行を担当します。
 
+pub mod auth_store;
 pub mod connection;
+pub mod events;
+pub mod feed_status;
+pub mod jobs;
+pub mod memory;
 pub mod models;
+pub mod overrides;
+pub mod related;
 pub mod repository;
-pub mod simple_repository;
+pub mod search;
+pub mod stats;
+pub mod store;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod trending;
+
+pub use auth_store::{
+    AuthRepository, AuthStore, InMemoryAuthStore, NewAuditEntry, SharedAuthRepository,
+};
+pub use events::{NewsEvent, NewsEventHub, NewsEventKind};
+pub use feed_status::{record_feed_fetch, FeedFetch, FeedStatus};
+pub use jobs::{Job, JobKind, JobStatus, STALE_JOB_FAILURE};
+pub use memory::InMemoryNewsStore;
+pub use overrides::{NewsOverride, OverrideChange, OverrideField, StoredOverride, TrainingLabel};
+pub use related::{RelatedDocument, RelatedNews, RelatedNewsCache};
+pub use repository::{
+    content_version, fetch_news_page, save_scraped_news, ContentVersion, NewsCursor, NewsFilter,
+    NewsPage, NewsPageResult, NewsRepository, PoolStatus, SaveResult, SharedNewsRepository,
+    SourceCounts,
+};
+pub use search::{SearchHit, SearchLanguage};
+pub use stats::{StatsBucket, StatsGranularity, StatsGroup, StatsGroupBy, StatsQuery};
+pub use store::NewsStore;
+pub use trending::{TrendingEntry, TrendingKind, TrendingSnapshot, TrendingWindow};
 
 // Note: create_pool is now in the connection module and returns PgPool
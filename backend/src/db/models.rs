@@ -1,50 +1,102 @@
+//! データベースの行に対応する型定義
+//!
+//! `migrations_postgres`で作成されるテーブルと1対1で対応します。
+//! クエリは[`NewsStore`](super::store::NewsStore)でコンパイル時にチェックされます。
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-/// チーム情報
+/// チーム情報（`teams`テーブル）
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Team {
-    pub id: i64,
-    pub code: String,
+    /// チームID（略称と同じ。例: LAL）
+    pub id: String,
     pub name: String,
     pub name_ja: Option<String>,
+    pub abbreviation: String,
+    pub city: String,
     pub conference: String,
     pub division: String,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
-/// トレードニュース
+/// トレードニュース（`trade_news`テーブル）
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TradeNews {
-    pub id: i64,
-    pub external_id: String,
+    /// RSS GUIDまたはリンクのハッシュ
+    pub id: String,
     pub title: String,
     pub description: Option<String>,
+    /// ニュースソース（ESPN、RealGMなど）
+    pub source: String,
+    /// 記事のURL
+    pub link: String,
+    pub author: Option<String>,
+    /// カテゴリー（Trade、Signing、Other）
+    pub category: String,
+    pub team_id: Option<String>,
+    pub is_official: bool,
+    pub published_at: DateTime<Utc>,
+    pub scraped_at: Option<DateTime<Utc>>,
     pub title_ja: Option<String>,
     pub description_ja: Option<String>,
+    /// 翻訳ステータス（pending、completed、failed）
     pub translation_status: String,
-    pub translated_at: Option<String>,
-    pub source_name: String,
-    pub source_url: String,
-    pub author: Option<String>,
-    pub category: String,
-    pub is_official: Option<bool>,
-    pub published_at: String,
-    pub scraped_at: Option<String>,
-    pub created_at: Option<String>,
-    pub updated_at: Option<String>,
+    pub translated_at: Option<DateTime<Utc>>,
+    pub image_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    /// タイトルと説明文のハッシュ
+    pub content_hash: Option<String>,
+    /// 内容が更新されるたびに増えるリビジョン番号
+    pub revision: i32,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
-/// トレードニュース作成用の入力データ
-#[derive(Debug, Clone)]
-pub struct NewTradeNews {
-    pub external_id: String,
+/// 記事の過去バージョン（`trade_news_revisions`テーブル）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TradeNewsRevision {
+    pub id: i64,
+    pub news_id: String,
+    pub revision: i32,
     pub title: String,
     pub description: Option<String>,
-    pub source_name: String,
-    pub source_url: String,
     pub category: String,
-    pub published_at: DateTime<Utc>,
+    pub title_ja: Option<String>,
+    pub description_ja: Option<String>,
+    pub content_hash: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// APIキー（`api_keys`テーブル）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    /// キーの用途を表す一意な名前
+    pub name: String,
+    /// キーの先頭部分（一覧での識別用）
+    pub key_prefix: String,
+    /// キー本体のSHA-256ハッシュ
+    pub key_hash: String,
+    /// ロール（reader、editor、admin）
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// 監査ログ（`audit_log`テーブル）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// 操作した主体（例: api_key:ci、jwt:alice、anonymous）
+    pub actor: String,
+    pub role: Option<String>,
+    /// 操作（GraphQLのフィールド名やCLIのコマンド）
+    pub action: String,
+    pub success: bool,
+    pub details: Option<String>,
+    pub recorded_at: DateTime<Utc>,
 }
@@ -0,0 +1,377 @@
+//! 編集者による分類の上書き
+//!
+//! カテゴリー・関連チーム・関連選手・公式発表・非表示を、分類器の出力とは別の
+//! `news_overrides`に保存します。記事が再スクレイピングで分類し直されても、
+//! 保存先は上書きを反映し直すため、編集者の判断が消えることはありません。
+//!
+//! 変更は編集者と日時付きで履歴に残し、上書きした値は分類器の出力と並べて
+//! 教師データ（[`TrainingLabel`]）として取り出せます。
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+
+use crate::db::models::TradeNews;
+use crate::scraper::models::NewsItem;
+use crate::scraper::players::{detect_player_ids, NBA_PLAYERS};
+use crate::scraper::teams::{detect_team_ids, NBA_TEAMS};
+
+/// 上書きできるカテゴリー
+pub const CATEGORIES: [&str; 3] = ["Trade", "Signing", "Other"];
+
+/// 上書きできる項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverrideField {
+    Category,
+    Teams,
+    Players,
+    IsOfficial,
+    Hidden,
+}
+
+impl OverrideField {
+    pub const ALL: [OverrideField; 5] = [
+        OverrideField::Category,
+        OverrideField::Teams,
+        OverrideField::Players,
+        OverrideField::IsOfficial,
+        OverrideField::Hidden,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OverrideField::Category => "category",
+            OverrideField::Teams => "teams",
+            OverrideField::Players => "players",
+            OverrideField::IsOfficial => "is_official",
+            OverrideField::Hidden => "hidden",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.as_str() == value)
+    }
+}
+
+/// 編集者が指定した値（`None`の項目は分類器の出力のまま）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewsOverride {
+    pub category: Option<String>,
+    pub team_ids: Option<Vec<String>>,
+    pub player_ids: Option<Vec<String>>,
+    pub is_official: Option<bool>,
+    pub hidden: Option<bool>,
+}
+
+impl NewsOverride {
+    /// 値の正規化（チームIDは大文字、選手IDは小文字、ID順で重複なし）
+    pub fn normalized(mut self) -> Self {
+        let normalize = |ids: &mut Vec<String>, upper: bool| {
+            for id in ids.iter_mut() {
+                *id = if upper {
+                    id.trim().to_uppercase()
+                } else {
+                    id.trim().to_lowercase()
+                };
+            }
+            ids.sort();
+            ids.dedup();
+        };
+        if let Some(ids) = &mut self.team_ids {
+            normalize(ids, true);
+        }
+        if let Some(ids) = &mut self.player_ids {
+            normalize(ids, false);
+        }
+        self
+    }
+
+    /// 存在しないカテゴリー・チーム・選手が指定されていないか確認する
+    pub fn validate(&self) -> Result<()> {
+        if self.values().is_empty() {
+            bail!("No override values were given");
+        }
+        if let Some(category) = &self.category {
+            if !CATEGORIES.contains(&category.as_str()) {
+                bail!(
+                    "Unknown category: {} (expected one of {})",
+                    category,
+                    CATEGORIES.join(", ")
+                );
+            }
+        }
+        for id in self.team_ids.iter().flatten() {
+            if !NBA_TEAMS.iter().any(|(team_id, ..)| team_id == id) {
+                bail!("Unknown team: {}", id);
+            }
+        }
+        for id in self.player_ids.iter().flatten() {
+            if !NBA_PLAYERS.iter().any(|(player_id, ..)| player_id == id) {
+                bail!("Unknown player: {}", id);
+            }
+        }
+        Ok(())
+    }
+
+    /// `patch`で指定された項目を取り込む
+    pub fn merge(&mut self, patch: &NewsOverride) {
+        if let Some(category) = &patch.category {
+            self.category = Some(category.clone());
+        }
+        if let Some(ids) = &patch.team_ids {
+            self.team_ids = Some(ids.clone());
+        }
+        if let Some(ids) = &patch.player_ids {
+            self.player_ids = Some(ids.clone());
+        }
+        if let Some(is_official) = patch.is_official {
+            self.is_official = Some(is_official);
+        }
+        if let Some(hidden) = patch.hidden {
+            self.hidden = Some(hidden);
+        }
+    }
+
+    /// 指定された項目と、履歴に記録する形式の値
+    pub fn values(&self) -> Vec<(OverrideField, String)> {
+        let mut values = Vec::new();
+        if let Some(category) = &self.category {
+            values.push((OverrideField::Category, category.clone()));
+        }
+        if let Some(ids) = &self.team_ids {
+            values.push((OverrideField::Teams, ids.join(",")));
+        }
+        if let Some(ids) = &self.player_ids {
+            values.push((OverrideField::Players, ids.join(",")));
+        }
+        if let Some(is_official) = self.is_official {
+            values.push((OverrideField::IsOfficial, is_official.to_string()));
+        }
+        if let Some(hidden) = self.hidden {
+            values.push((OverrideField::Hidden, hidden.to_string()));
+        }
+        values
+    }
+}
+
+/// 記事に保存されている上書き
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredOverride {
+    pub news_id: String,
+    pub values: NewsOverride,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 記事の現在の値（上書きを反映したもの）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentValues {
+    pub category: String,
+    pub team_ids: Vec<String>,
+    pub player_ids: Vec<String>,
+    pub is_official: bool,
+    pub hidden: bool,
+}
+
+impl CurrentValues {
+    /// 分類器が出力する値（上書きがない状態）
+    pub fn predicted(title: &str, description: Option<&str>) -> Self {
+        Self {
+            category: NewsItem::determine_category(title, description),
+            team_ids: detect_team_ids(title, description),
+            player_ids: detect_player_ids(title, description),
+            is_official: false,
+            hidden: false,
+        }
+    }
+
+    /// 履歴に記録する形式の値
+    pub fn value(&self, field: OverrideField) -> String {
+        match field {
+            OverrideField::Category => self.category.clone(),
+            OverrideField::Teams => self.team_ids.join(","),
+            OverrideField::Players => self.player_ids.join(","),
+            OverrideField::IsOfficial => self.is_official.to_string(),
+            OverrideField::Hidden => self.hidden.to_string(),
+        }
+    }
+}
+
+/// 上書きの変更履歴の1件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverrideChange {
+    pub id: i64,
+    pub news_id: String,
+    pub field: OverrideField,
+    /// 変更前の値（チーム・選手はカンマ区切りのID）
+    pub previous_value: Option<String>,
+    /// 変更後の値（`None`は上書きを取り消して分類器の出力に戻したもの）
+    pub new_value: Option<String>,
+    /// 変更した編集者（認証された主体）
+    pub editor: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// 履歴に記録する変更（項目、変更前、変更後）
+pub(crate) type PendingChange = (OverrideField, Option<String>, Option<String>);
+
+/// 上書きを適用するときの変更
+pub(crate) fn override_changes(
+    current: &CurrentValues,
+    patch: &NewsOverride,
+) -> Vec<PendingChange> {
+    patch
+        .values()
+        .into_iter()
+        .map(|(field, value)| (field, Some(current.value(field)), Some(value)))
+        .collect()
+}
+
+/// 上書きを取り消すときの変更
+pub(crate) fn clear_changes(current: &CurrentValues, stored: &NewsOverride) -> Vec<PendingChange> {
+    stored
+        .values()
+        .into_iter()
+        .map(|(field, _)| (field, Some(current.value(field)), None))
+        .collect()
+}
+
+/// 分類器の出力と編集者の判断を並べた教師データ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrainingLabel {
+    pub news_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub field: OverrideField,
+    /// 分類器の出力
+    pub predicted: String,
+    /// 編集者が付けた正解
+    pub label: String,
+    pub labeled_by: String,
+    pub labeled_at: DateTime<Utc>,
+}
+
+/// 上書きされた記事から教師データを作る（上書きされた項目ごとに1件）
+pub fn training_labels(news: &TradeNews, stored: &StoredOverride) -> Vec<TrainingLabel> {
+    let predicted = CurrentValues::predicted(&news.title, news.description.as_deref());
+    stored
+        .values
+        .values()
+        .into_iter()
+        .map(|(field, label)| TrainingLabel {
+            news_id: news.id.clone(),
+            title: news.title.clone(),
+            description: news.description.clone(),
+            field,
+            predicted: predicted.value(field),
+            label,
+            labeled_by: stored.updated_by.clone(),
+            labeled_at: stored.updated_at,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_validate() {
+        let patch = NewsOverride {
+            team_ids: Some(vec![
+                "lal".to_string(),
+                " BOS".to_string(),
+                "LAL".to_string(),
+            ]),
+            player_ids: Some(vec!["LeBron-James".to_string()]),
+            ..NewsOverride::default()
+        }
+        .normalized();
+        assert_eq!(
+            patch.team_ids,
+            Some(vec!["BOS".to_string(), "LAL".to_string()])
+        );
+        assert_eq!(patch.player_ids, Some(vec!["lebron-james".to_string()]));
+        assert!(patch.validate().is_ok());
+
+        assert!(NewsOverride::default().validate().is_err());
+        let unknown = |patch: NewsOverride| patch.validate().unwrap_err().to_string();
+        assert!(unknown(NewsOverride {
+            category: Some("Rumor".to_string()),
+            ..NewsOverride::default()
+        })
+        .starts_with("Unknown category"));
+        assert_eq!(
+            unknown(NewsOverride {
+                team_ids: Some(vec!["XYZ".to_string()]),
+                ..NewsOverride::default()
+            }),
+            "Unknown team: XYZ"
+        );
+    }
+
+    #[test]
+    fn test_changes_and_training_labels() {
+        let current = CurrentValues::predicted("Lakers sign guard", None);
+        let patch = NewsOverride {
+            category: Some("Trade".to_string()),
+            hidden: Some(true),
+            ..NewsOverride::default()
+        };
+
+        assert_eq!(
+            override_changes(&current, &patch),
+            vec![
+                (
+                    OverrideField::Category,
+                    Some("Signing".to_string()),
+                    Some("Trade".to_string())
+                ),
+                (
+                    OverrideField::Hidden,
+                    Some("false".to_string()),
+                    Some("true".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            clear_changes(&current, &patch)[0],
+            (OverrideField::Category, Some("Signing".to_string()), None)
+        );
+
+        let news = TradeNews {
+            id: "label-1".to_string(),
+            title: "Lakers sign guard".to_string(),
+            description: None,
+            source: "ESPN".to_string(),
+            link: "https://example.com/label-1".to_string(),
+            author: None,
+            category: "Trade".to_string(),
+            team_id: None,
+            is_official: false,
+            published_at: Utc::now(),
+            scraped_at: None,
+            title_ja: None,
+            description_ja: None,
+            translation_status: "pending".to_string(),
+            translated_at: None,
+            image_url: None,
+            thumbnail_url: None,
+            content_hash: None,
+            revision: 1,
+            created_at: None,
+            updated_at: None,
+        };
+        let stored = StoredOverride {
+            news_id: news.id.clone(),
+            values: patch,
+            updated_by: "jwt:editor".to_string(),
+            updated_at: Utc::now(),
+        };
+        let labels = training_labels(&news, &stored);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].field, OverrideField::Category);
+        assert_eq!(labels[0].predicted, "Signing");
+        assert_eq!(labels[0].label, "Trade");
+        assert_eq!(labels[0].labeled_by, "jwt:editor");
+    }
+}
@@ -0,0 +1,573 @@
+//! 記事の関連ニュース
+//!
+//! 記事の詳細画面に出す関連ニュースを、保存済みの記事からRustで計算します。
+//! 候補の記事ごとに次の4つを重み付けして足し合わせ、大きい順に並べます。
+//!
+//! - 関連チーム・選手の重なり（Jaccard係数、0.35）
+//! - 同じ話題のまとまり（ストーリー）に属するか（0.2）
+//! - タイトル・説明文のTF-IDFベクトルのコサイン類似度（0.3）
+//! - 公開日時の近さ（3日で1/eになる指数減衰、0.15）
+//!
+//! ストーリーは、公開日時が72時間以内でコサイン類似度が0.5以上の記事どうしを
+//! つないだまとまりです（つながりをたどった先も同じストーリーとします）。
+//!
+//! TF-IDFベクトルとストーリーは最新の記事（[`MAX_CORPUS_SIZE`]件）から作る索引に
+//! まとめ、[`RelatedNewsCache`]に保持します。記事が保存・翻訳されるか、作ってから
+//! 編集者が上書きするか、作ってから[`INDEX_MAX_AGE_MINUTES`]分たつと作り直します。
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::db::repository::{content_version, ContentVersion, NewsRepository};
+use crate::utils::string_utils::strip_html_tags;
+
+/// 索引に入れる記事の最大数（新しい順）
+pub const MAX_CORPUS_SIZE: i64 = 2000;
+
+/// 1回に返す関連ニュースの最大数
+pub const MAX_RELATED_NEWS: usize = 20;
+
+/// 索引を作り直すまでの最長の時間
+pub const INDEX_MAX_AGE_MINUTES: i64 = 10;
+
+const ENTITY_WEIGHT: f64 = 0.35;
+const STORY_WEIGHT: f64 = 0.2;
+const TEXT_WEIGHT: f64 = 0.3;
+const TIME_WEIGHT: f64 = 0.15;
+
+/// 同じストーリーとみなすコサイン類似度
+const STORY_SIMILARITY: f64 = 0.5;
+
+/// 同じストーリーとみなす公開日時の差
+const STORY_WINDOW_HOURS: i64 = 72;
+
+/// 公開日時の近さのスコアが1/eになる日数
+const TIME_DECAY_DAYS: f64 = 3.0;
+
+/// チーム・選手・ストーリーが共通せず、これより似ていない記事は関連とみなさない
+const MIN_SIMILARITY: f64 = 0.05;
+
+/// タイトルの単語は説明文の単語の何回分として数えるか
+const TITLE_TERM_WEIGHT: usize = 2;
+
+/// TF-IDFで無視する単語
+const STOP_WORDS: [&str; 40] = [
+    "a", "an", "and", "are", "as", "at", "be", "been", "but", "by", "for", "from", "has", "have",
+    "he", "his", "in", "into", "is", "it", "its", "new", "of", "on", "or", "over", "per", "say",
+    "says", "sources", "that", "the", "their", "they", "this", "to", "was", "will", "with", "who",
+];
+
+/// 関連ニュースの計算に使う記事の内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedDocument {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub team_ids: Vec<String>,
+    pub player_ids: Vec<String>,
+}
+
+/// 関連ニュースの1件
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedNews {
+    pub id: String,
+    /// 関連度（0〜1）
+    pub score: f64,
+    /// タイトル・説明文のコサイン類似度（0〜1）
+    pub similarity: f64,
+    /// 同じストーリーに属するか
+    pub same_story: bool,
+    /// 共通する関連チームのID
+    pub shared_team_ids: Vec<String>,
+    /// 共通する関連選手のID
+    pub shared_player_ids: Vec<String>,
+}
+
+/// 単語IDと重みの組（単語ID順、長さ1に正規化）
+type SparseVector = Vec<(usize, f64)>;
+
+struct IndexedDocument {
+    document: RelatedDocument,
+    vector: SparseVector,
+    story: usize,
+}
+
+/// TF-IDFベクトルとストーリーの索引
+pub struct RelatedIndex {
+    documents: Vec<IndexedDocument>,
+    positions: HashMap<String, usize>,
+    vocabulary: HashMap<String, usize>,
+    idf: Vec<f64>,
+    /// 索引を作ったときの記事の版
+    version: ContentVersion,
+    built_at: DateTime<Utc>,
+}
+
+impl RelatedIndex {
+    /// 記事からTF-IDFベクトルとストーリーを作る
+    pub fn build(
+        documents: Vec<RelatedDocument>,
+        version: ContentVersion,
+        built_at: DateTime<Utc>,
+    ) -> Self {
+        let terms: Vec<HashMap<String, usize>> = documents.iter().map(term_counts).collect();
+
+        let mut vocabulary = HashMap::new();
+        let mut document_frequency: Vec<usize> = Vec::new();
+        for counts in &terms {
+            for term in counts.keys() {
+                let id = *vocabulary.entry(term.clone()).or_insert_with(|| {
+                    document_frequency.push(0);
+                    document_frequency.len() - 1
+                });
+                document_frequency[id] += 1;
+            }
+        }
+        let total = documents.len() as f64;
+        let idf = document_frequency
+            .iter()
+            .map(|&df| ((total + 1.0) / (df as f64 + 1.0)).ln() + 1.0)
+            .collect();
+
+        let mut index = Self {
+            documents: Vec::with_capacity(documents.len()),
+            positions: HashMap::new(),
+            vocabulary,
+            idf,
+            version,
+            built_at,
+        };
+        for (position, (document, counts)) in documents.into_iter().zip(terms).enumerate() {
+            index.positions.insert(document.id.clone(), position);
+            let vector = index.vectorize(&counts);
+            index.documents.push(IndexedDocument {
+                document,
+                vector,
+                story: position,
+            });
+        }
+        index.assign_stories();
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// 索引を作り直さずに使えるか
+    pub fn is_fresh(&self, version: ContentVersion, now: DateTime<Utc>) -> bool {
+        self.version == version && now - self.built_at < Duration::minutes(INDEX_MAX_AGE_MINUTES)
+    }
+
+    fn vectorize(&self, counts: &HashMap<String, usize>) -> SparseVector {
+        let mut vector: SparseVector = counts
+            .iter()
+            .filter_map(|(term, &count)| {
+                let id = *self.vocabulary.get(term)?;
+                Some((id, (1.0 + (count as f64).ln()) * self.idf[id]))
+            })
+            .collect();
+        let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            for (_, weight) in &mut vector {
+                *weight /= norm;
+            }
+        }
+        vector.sort_by_key(|(id, _)| *id);
+        vector
+    }
+
+    /// 公開日時が近く内容の似た記事をつなぎ、ストーリーに分ける
+    fn assign_stories(&mut self) {
+        let mut order: Vec<usize> = (0..self.documents.len()).collect();
+        order.sort_by_key(|&i| self.documents[i].document.published_at);
+        let window = Duration::hours(STORY_WINDOW_HOURS);
+
+        let mut stories = UnionFind::new(self.documents.len());
+        for (n, &i) in order.iter().enumerate() {
+            let a = &self.documents[i];
+            for &j in &order[n + 1..] {
+                let b = &self.documents[j];
+                if b.document.published_at - a.document.published_at > window {
+                    break;
+                }
+                if cosine(&a.vector, &b.vector) >= STORY_SIMILARITY {
+                    stories.union(i, j);
+                }
+            }
+        }
+        for i in 0..self.documents.len() {
+            self.documents[i].story = stories.find(i);
+        }
+    }
+
+    /// 記事の関連ニュース（関連度の大きい順）
+    ///
+    /// 索引にない記事（古い記事など）は、索引の単語の重みでベクトルを作って比べます。
+    pub fn related(&self, target: &RelatedDocument, limit: usize) -> Vec<RelatedNews> {
+        let (vector, stories) = match self.positions.get(&target.id) {
+            Some(&position) => {
+                let indexed = &self.documents[position];
+                (indexed.vector.clone(), HashSet::from([indexed.story]))
+            }
+            None => {
+                let vector = self.vectorize(&term_counts(target));
+                let window = Duration::hours(STORY_WINDOW_HOURS);
+                let stories = self
+                    .documents
+                    .iter()
+                    .filter(|other| {
+                        (other.document.published_at - target.published_at).abs() <= window
+                            && cosine(&vector, &other.vector) >= STORY_SIMILARITY
+                    })
+                    .map(|other| other.story)
+                    .collect();
+                (vector, stories)
+            }
+        };
+
+        let mut related: Vec<(RelatedNews, DateTime<Utc>)> = self
+            .documents
+            .iter()
+            .filter(|other| other.document.id != target.id)
+            .filter_map(|other| {
+                let candidate = &other.document;
+                let shared_team_ids = intersection(&target.team_ids, &candidate.team_ids);
+                let shared_player_ids = intersection(&target.player_ids, &candidate.player_ids);
+                let similarity = cosine(&vector, &other.vector);
+                let same_story = stories.contains(&other.story);
+                if shared_team_ids.is_empty()
+                    && shared_player_ids.is_empty()
+                    && !same_story
+                    && similarity < MIN_SIMILARITY
+                {
+                    return None;
+                }
+
+                let union = union_size(&target.team_ids, &candidate.team_ids)
+                    + union_size(&target.player_ids, &candidate.player_ids);
+                let entity_overlap = if union == 0 {
+                    0.0
+                } else {
+                    (shared_team_ids.len() + shared_player_ids.len()) as f64 / union as f64
+                };
+                let days = (candidate.published_at - target.published_at)
+                    .num_seconds()
+                    .abs() as f64
+                    / 86_400.0;
+                let proximity = (-days / TIME_DECAY_DAYS).exp();
+                let score = ENTITY_WEIGHT * entity_overlap
+                    + STORY_WEIGHT * if same_story { 1.0 } else { 0.0 }
+                    + TEXT_WEIGHT * similarity
+                    + TIME_WEIGHT * proximity;
+
+                Some((
+                    RelatedNews {
+                        id: candidate.id.clone(),
+                        score,
+                        similarity,
+                        same_story,
+                        shared_team_ids,
+                        shared_player_ids,
+                    },
+                    candidate.published_at,
+                ))
+            })
+            .collect();
+
+        related.sort_by(|(a, a_published), (b, b_published)| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b_published.cmp(a_published))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        related.truncate(limit);
+        related.into_iter().map(|(news, _)| news).collect()
+    }
+}
+
+/// 作った索引を保持し、古くなったら作り直す
+#[derive(Clone, Default)]
+pub struct RelatedNewsCache {
+    index: Arc<Mutex<Option<Arc<RelatedIndex>>>>,
+}
+
+impl RelatedNewsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使える索引を返す（なければ、または古ければ作り直す）
+    ///
+    /// 作り直している間は他のリクエストを待たせ、同じ索引を何度も作らないようにします。
+    pub async fn index(
+        &self,
+        repository: &dyn NewsRepository,
+        now: DateTime<Utc>,
+    ) -> Result<Arc<RelatedIndex>> {
+        let version = content_version(repository).await?;
+        let mut cached = self.index.lock().await;
+        if let Some(index) = cached.as_ref().filter(|index| index.is_fresh(version, now)) {
+            return Ok(index.clone());
+        }
+
+        let documents = repository.related_corpus(MAX_CORPUS_SIZE).await?;
+        let index = Arc::new(RelatedIndex::build(documents, version, now));
+        *cached = Some(index.clone());
+        Ok(index)
+    }
+}
+
+/// 記事の関連ニュースを返す（記事がなければ`None`）
+pub async fn related_news(
+    repository: &dyn NewsRepository,
+    cache: &RelatedNewsCache,
+    id: &str,
+    limit: usize,
+    now: DateTime<Utc>,
+) -> Result<Option<Vec<RelatedNews>>> {
+    let Some(target) = repository.related_document(id).await? else {
+        return Ok(None);
+    };
+    let index = cache.index(repository, now).await?;
+    Ok(Some(index.related(&target, limit.min(MAX_RELATED_NEWS))))
+}
+
+/// 記事の単語と出現回数（タイトルの単語は重く数える）
+fn term_counts(document: &RelatedDocument) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for term in tokenize(&document.title) {
+        *counts.entry(term).or_insert(0) += TITLE_TERM_WEIGHT;
+    }
+    for term in document
+        .description
+        .as_deref()
+        .map(tokenize)
+        .unwrap_or_default()
+    {
+        *counts.entry(term).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// HTMLタグを除いて小文字の単語に分ける（1文字の単語・数字・よく使う単語は除く）
+fn tokenize(text: &str) -> Vec<String> {
+    strip_html_tags(text)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| {
+            term.chars().count() > 1
+                && !term.chars().all(|c| c.is_ascii_digit())
+                && !STOP_WORDS.contains(term)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// 長さ1に正規化したベクトルのコサイン類似度
+fn cosine(a: &SparseVector, b: &SparseVector) -> f64 {
+    let (mut i, mut j, mut dot) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                dot += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    dot.clamp(0.0, 1.0)
+}
+
+fn intersection(a: &[String], b: &[String]) -> Vec<String> {
+    let mut shared: Vec<String> = a.iter().filter(|id| b.contains(id)).cloned().collect();
+    shared.sort();
+    shared.dedup();
+    shared
+}
+
+fn union_size(a: &[String], b: &[String]) -> usize {
+    a.iter().chain(b).collect::<HashSet<_>>().len()
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut node = i;
+        while self.parents[node] != root {
+            let next = self.parents[node];
+            self.parents[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[b.max(a)] = a.min(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(id: &str, hours_ago: i64, title: &str, teams: &[&str]) -> RelatedDocument {
+        let now = DateTime::parse_from_rfc3339("2025-08-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        RelatedDocument {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: None,
+            published_at: now - Duration::hours(hours_ago),
+            team_ids: teams.iter().map(|id| id.to_string()).collect(),
+            player_ids: Vec::new(),
+        }
+    }
+
+    /// 固定のコーパス（ドンチッチのトレードの続報2件、関係の薄い記事、無関係の記事）
+    fn corpus() -> Vec<RelatedDocument> {
+        let mut luka = document(
+            "luka-1",
+            2,
+            "Mavericks trade Luka Doncic to Lakers for Anthony Davis",
+            &["DAL", "LAL"],
+        );
+        luka.player_ids = vec!["luka-doncic".to_string(), "anthony-davis".to_string()];
+        luka.description = Some("Blockbuster trade sends Doncic to Los Angeles".to_string());
+
+        let mut follow_up = document(
+            "luka-2",
+            20,
+            "Luka Doncic trade: Lakers and Mavericks finalize Davis deal",
+            &["DAL", "LAL"],
+        );
+        follow_up.player_ids = vec!["luka-doncic".to_string()];
+
+        let mut reaction = document(
+            "luka-3",
+            60,
+            "Doncic trade reaction: Mavericks fans stunned by Davis deal",
+            &["DAL"],
+        );
+        reaction.player_ids = vec!["luka-doncic".to_string()];
+
+        vec![
+            luka,
+            follow_up,
+            reaction,
+            document(
+                "lakers-signing",
+                30,
+                "Lakers sign veteran guard to minimum contract",
+                &["LAL"],
+            ),
+            document(
+                "old-lakers",
+                24 * 60,
+                "Lakers sign veteran guard to minimum contract",
+                &["LAL"],
+            ),
+            document(
+                "celtics",
+                5,
+                "Celtics extend Jayson Tatum through 2030",
+                &["BOS"],
+            ),
+        ]
+    }
+
+    fn index() -> RelatedIndex {
+        RelatedIndex::build(corpus(), ContentVersion::default(), Utc::now())
+    }
+
+    #[test]
+    fn test_related_ranks_same_story_first() {
+        let index = index();
+        let target = corpus().remove(0);
+        let related = index.related(&target, 10);
+        let ids: Vec<&str> = related.iter().map(|news| news.id.as_str()).collect();
+
+        assert_eq!(ids[..2], ["luka-2", "luka-3"]);
+        assert!(related[0].same_story && related[1].same_story);
+        assert_eq!(related[0].shared_team_ids, ["DAL", "LAL"]);
+        assert_eq!(related[0].shared_player_ids, ["luka-doncic"]);
+
+        // 関連チームしか共通しない記事は後ろ、新しい方が上
+        assert_eq!(ids[2..], ["lakers-signing", "old-lakers"]);
+        assert!(!related[2].same_story);
+
+        // チームも単語も共通しない記事は出さない
+        assert!(!ids.contains(&"celtics"));
+        assert!(!ids.contains(&"luka-1"));
+    }
+
+    #[test]
+    fn test_stories_link_similar_articles_in_time() {
+        let index = index();
+        let story = |id: &str| index.documents[index.positions[id]].story;
+
+        assert_eq!(story("luka-1"), story("luka-3"));
+        // 同じ見出しでも公開日時が離れていれば別のストーリー
+        assert_ne!(story("lakers-signing"), story("old-lakers"));
+        assert_ne!(story("luka-1"), story("celtics"));
+    }
+
+    #[test]
+    fn test_related_for_document_outside_index() {
+        let index = index();
+        let mut target = document(
+            "luka-4",
+            1,
+            "Lakers introduce Luka Doncic after Mavericks trade",
+            &["LAL"],
+        );
+        target.player_ids = vec!["luka-doncic".to_string()];
+
+        let related = index.related(&target, 2);
+        let ids: Vec<&str> = related.iter().map(|news| news.id.as_str()).collect();
+        assert_eq!(ids, ["luka-2", "luka-1"]);
+        assert!(related.iter().all(|news| news.same_story));
+        assert!(related.iter().all(|news| news.score <= 1.0));
+    }
+
+    #[test]
+    fn test_tokenize_and_cosine() {
+        assert_eq!(
+            tokenize("The <b>Lakers</b> trade for D'Angelo in 2025"),
+            ["lakers", "trade", "angelo"]
+        );
+
+        let index = index();
+        let vector = |id: &str| &index.documents[index.positions[id]].vector;
+        let same = cosine(vector("luka-1"), vector("luka-1"));
+        assert!((same - 1.0).abs() < 1e-9);
+        assert_eq!(cosine(vector("luka-1"), vector("celtics")), 0.0);
+    }
+}
@@ -0,0 +1,222 @@
+//! ニュースの全文検索
+//!
+//! 英語のタイトル・説明文はPostgreSQLの`tsvector`で、単語に分割できない
+//! 日本語訳はトライグラム（`pg_trgm`）で検索します。
+//! ここではリポジトリ実装に共通する型とスニペット生成を定義します。
+
+use crate::db::models::TradeNews;
+use crate::utils::string_utils::strip_html_tags;
+
+/// スニペット内で一致箇所を囲む開始タグ
+pub const HIGHLIGHT_START: &str = "<mark>";
+/// スニペット内で一致箇所を囲む終了タグ
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// スニペットの最大文字数（省略記号とタグを除く）
+pub(crate) const SNIPPET_MAX_CHARS: usize = 120;
+
+/// 検索対象の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchLanguage {
+    /// 原文（`title`・`description`）
+    English,
+    /// 日本語訳（`title_ja`・`description_ja`）
+    Japanese,
+}
+
+/// 検索結果の1件
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub news: TradeNews,
+    /// 関連度（大きいほど上位）
+    pub rank: f32,
+    /// 一致箇所を`<mark>`で囲んだ抜粋（HTMLタグは除去済み）
+    pub snippet: String,
+}
+
+/// 検索文字列を空白で区切った語に分ける
+pub(crate) fn search_terms(query: &str) -> Vec<String> {
+    query.split_whitespace().map(str::to_string).collect()
+}
+
+/// LIKEパターンの特殊文字をエスケープし、部分一致のパターンにする
+pub(crate) fn like_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// 本文から一致箇所の周辺を切り出し、一致した語を`<mark>`で囲む
+///
+/// 大文字小文字は区別しません。一致がない場合は先頭から切り出します。
+pub(crate) fn highlight_snippet(text: &str, terms: &[String], max_chars: usize) -> String {
+    let chars: Vec<char> = strip_html_tags(text).chars().collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|term| term.chars().collect::<Vec<_>>())
+        .filter(|term| !term.is_empty())
+        .collect();
+    let matches = find_matches(&chars, &terms);
+
+    // 最初の一致箇所が前から3分の1あたりに来るように切り出す
+    let start = matches
+        .first()
+        .map(|&(begin, _)| begin.saturating_sub(max_chars / 3))
+        .unwrap_or(0);
+    let end = (start + max_chars).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut pos = start;
+    for &(begin, finish) in matches
+        .iter()
+        .filter(|&&(begin, finish)| begin >= start && finish <= end)
+    {
+        snippet.extend(&chars[pos..begin]);
+        snippet.push_str(HIGHLIGHT_START);
+        snippet.extend(&chars[begin..finish]);
+        snippet.push_str(HIGHLIGHT_END);
+        pos = finish;
+    }
+    snippet.extend(&chars[pos..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// スニペットの元にする本文を選ぶ
+///
+/// 説明文に語が含まれていれば説明文を、そうでなければタイトルを使います。
+pub(crate) fn snippet_source<'a>(
+    title: &'a str,
+    description: Option<&'a str>,
+    terms: &[String],
+) -> &'a str {
+    match description {
+        Some(description) if contains_any_term(description, terms) => description,
+        _ => title,
+    }
+}
+
+fn contains_any_term(text: &str, terms: &[String]) -> bool {
+    let text = text.to_lowercase();
+    terms.iter().any(|term| text.contains(&term.to_lowercase()))
+}
+
+/// 本文中で語に一致する範囲（文字単位、重なりなし）を前から順に探す
+fn find_matches(chars: &[char], terms: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let longest = terms
+            .iter()
+            .filter(|term| starts_with_ignore_case(&chars[i..], term))
+            .map(Vec::len)
+            .max();
+        match longest {
+            Some(len) => {
+                matches.push((i, i + len));
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    matches
+}
+
+fn starts_with_ignore_case(chars: &[char], term: &[char]) -> bool {
+    chars.len() >= term.len()
+        && chars
+            .iter()
+            .zip(term)
+            .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// 語がすべて本文に含まれる場合、一致した回数を返す
+pub(crate) fn count_term_matches(text: &str, terms: &[String]) -> Option<usize> {
+    let text = text.to_lowercase();
+    let mut total = 0;
+    for term in terms {
+        let count = text.matches(&term.to_lowercase()).count();
+        if count == 0 {
+            return None;
+        }
+        total += count;
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        search_terms(query)
+    }
+
+    #[test]
+    fn test_highlight_snippet_marks_terms_case_insensitively() {
+        let snippet = highlight_snippet(
+            "Mavericks trade <b>Luka</b> Doncic to the Lakers",
+            &terms("luka lakers"),
+            SNIPPET_MAX_CHARS,
+        );
+        assert_eq!(
+            snippet,
+            "Mavericks trade <mark>Luka</mark> Doncic to the <mark>Lakers</mark>"
+        );
+    }
+
+    #[test]
+    fn test_highlight_snippet_trims_around_first_match() {
+        let text = format!(
+            "{} ルカ・ドンチッチがレイカーズへ {}",
+            "あ".repeat(50),
+            "い".repeat(50)
+        );
+        let snippet = highlight_snippet(&text, &terms("ドンチッチ"), 30);
+
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>ドンチッチ</mark>"));
+    }
+
+    #[test]
+    fn test_highlight_snippet_without_match_uses_head() {
+        let snippet = highlight_snippet("Short text", &terms("missing"), SNIPPET_MAX_CHARS);
+        assert_eq!(snippet, "Short text");
+    }
+
+    #[test]
+    fn test_snippet_source_prefers_matching_description() {
+        let terms = terms("doncic");
+        assert_eq!(
+            snippet_source("Title", Some("Luka Doncic traded"), &terms),
+            "Luka Doncic traded"
+        );
+        assert_eq!(
+            snippet_source("Doncic title", Some("No match"), &terms),
+            "Doncic title"
+        );
+        assert_eq!(snippet_source("Title", None, &terms), "Title");
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
+    #[test]
+    fn test_count_term_matches_requires_all_terms() {
+        assert_eq!(
+            count_term_matches("Luka to LA, luka again", &terms("LUKA")),
+            Some(2)
+        );
+        assert_eq!(count_term_matches("Luka to LA", &terms("luka kyrie")), None);
+    }
+}
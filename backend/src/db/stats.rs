@@ -0,0 +1,321 @@
+//! ダッシュボード向けの集計
+//!
+//! 公開日時を時・日・週の単位に区切り、カテゴリー・ソース・チーム・翻訳ステータス
+//! ごとの件数を数えます。区切りは指定したタイムゾーンの暦に従うため、
+//! `Asia/Tokyo`を指定すると「今日」は日本時間の0時から始まります（週は月曜始まり）。
+//! ここではリポジトリ実装に共通する型と区切りの計算を定義します。
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// 1回の集計で返す区切りの最大数
+pub const MAX_STATS_BUCKETS: usize = 1000;
+
+/// 集計の区切りの単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsGranularity {
+    Hour,
+    Day,
+    Week,
+}
+
+impl StatsGranularity {
+    /// PostgreSQLの`date_trunc`に渡す単位
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatsGranularity::Hour => "hour",
+            StatsGranularity::Day => "day",
+            StatsGranularity::Week => "week",
+        }
+    }
+}
+
+/// 件数を分ける観点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsGroupBy {
+    Category,
+    Source,
+    /// 関連チーム（1件の記事が複数のチームに数えられることがある）
+    Team,
+    TranslationStatus,
+}
+
+impl StatsGroupBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatsGroupBy::Category => "category",
+            StatsGroupBy::Source => "source",
+            StatsGroupBy::Team => "team",
+            StatsGroupBy::TranslationStatus => "translation_status",
+        }
+    }
+}
+
+/// 集計の条件
+#[derive(Debug, Clone)]
+pub struct StatsQuery {
+    /// この日時以降に公開された記事（含む）
+    pub from: DateTime<Utc>,
+    /// この日時より前に公開された記事（含まない）
+    pub to: DateTime<Utc>,
+    pub granularity: StatsGranularity,
+    /// 指定しなければ区切りごとの件数だけを数える
+    pub group_by: Option<StatsGroupBy>,
+    /// 区切りの基準にするタイムゾーン
+    pub timezone: Tz,
+}
+
+/// 区切りの中のグループごとの件数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsGroup {
+    pub key: String,
+    pub count: i64,
+}
+
+/// 1つの区切りの件数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsBucket {
+    /// 区切りの開始日時
+    pub start: DateTime<Utc>,
+    /// 区切りに含まれる記事の件数
+    pub count: i64,
+    /// グループごとの件数（多い順）
+    pub groups: Vec<StatsGroup>,
+}
+
+impl StatsQuery {
+    /// 期間と区切りの数を確認する
+    pub fn validate(&self) -> Result<()> {
+        if self.from >= self.to {
+            bail!("`from` must be earlier than `to`");
+        }
+        let buckets = self.bucket_starts().len();
+        if buckets > MAX_STATS_BUCKETS {
+            bail!(
+                "Too many buckets ({}); use a coarser granularity or a shorter range (max {})",
+                buckets,
+                MAX_STATS_BUCKETS
+            );
+        }
+        Ok(())
+    }
+
+    /// `at`を含む区切りの開始日時
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let local = at.with_timezone(&self.timezone).naive_local();
+        let start = match self.granularity {
+            StatsGranularity::Hour => local.date().and_hms_opt(local.hour(), 0, 0),
+            StatsGranularity::Day => local.date().and_hms_opt(0, 0, 0),
+            StatsGranularity::Week => {
+                let days = i64::from(local.weekday().num_days_from_monday());
+                (local.date() - Duration::days(days)).and_hms_opt(0, 0, 0)
+            }
+        };
+        start.map_or(at, |start| self.local_to_utc(start))
+    }
+
+    /// 期間を覆う区切りの開始日時（古い順）
+    ///
+    /// 区切りの数が上限を超える場合は、上限を1つ超えたところで打ち切ります。
+    pub fn bucket_starts(&self) -> Vec<DateTime<Utc>> {
+        let mut starts = Vec::new();
+        let mut start = self.bucket_start(self.from);
+        while start < self.to && starts.len() <= MAX_STATS_BUCKETS {
+            starts.push(start);
+            start = self.next_bucket_start(start);
+        }
+        starts
+    }
+
+    fn next_bucket_start(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        let local = start.with_timezone(&self.timezone).naive_local();
+        let next = match self.granularity {
+            StatsGranularity::Hour => return start + Duration::hours(1),
+            StatsGranularity::Day => local + Duration::days(1),
+            StatsGranularity::Week => local + Duration::weeks(1),
+        };
+        self.local_to_utc(next)
+    }
+
+    /// 現地時刻を日時に戻す（夏時間で存在しない時刻なら1時間後）
+    fn local_to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or_else(
+                || Utc.from_utc_datetime(&local),
+                |at| at.with_timezone(&Utc),
+            )
+    }
+}
+
+/// 区切りごとの件数を、件数のない区切りも含めて期間全体の一覧にする
+///
+/// `totals`は区切りの開始日時ごとの記事数、`groups`は（開始日時、キー、件数）です。
+pub(crate) fn assemble_buckets(
+    query: &StatsQuery,
+    totals: impl IntoIterator<Item = (DateTime<Utc>, i64)>,
+    groups: impl IntoIterator<Item = (DateTime<Utc>, String, i64)>,
+) -> Vec<StatsBucket> {
+    let mut buckets: Vec<StatsBucket> = query
+        .bucket_starts()
+        .into_iter()
+        .map(|start| StatsBucket {
+            start,
+            count: 0,
+            groups: Vec::new(),
+        })
+        .collect();
+    let index: HashMap<DateTime<Utc>, usize> = buckets
+        .iter()
+        .enumerate()
+        .map(|(index, bucket)| (bucket.start, index))
+        .collect();
+
+    for (start, count) in totals {
+        if let Some(&index) = index.get(&start) {
+            buckets[index].count += count;
+        }
+    }
+    for (start, key, count) in groups {
+        if let Some(&index) = index.get(&start) {
+            buckets[index].groups.push(StatsGroup { key, count });
+        }
+    }
+    for bucket in &mut buckets {
+        bucket
+            .groups
+            .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn query(granularity: StatsGranularity, from: &str, to: &str) -> StatsQuery {
+        StatsQuery {
+            from: utc(from),
+            to: utc(to),
+            granularity,
+            group_by: None,
+            timezone: chrono_tz::Asia::Tokyo,
+        }
+    }
+
+    #[test]
+    fn test_days_follow_the_japanese_calendar() {
+        let query = query(
+            StatsGranularity::Day,
+            "2025-08-01T00:00:00Z",
+            "2025-08-03T00:00:00Z",
+        );
+
+        // 日本時間の8月1日23時は8月1日、8月2日0時は8月2日
+        assert_eq!(
+            query.bucket_start(utc("2025-08-01T14:59:59Z")),
+            utc("2025-07-31T15:00:00Z")
+        );
+        assert_eq!(
+            query.bucket_start(utc("2025-08-01T15:00:00Z")),
+            utc("2025-08-01T15:00:00Z")
+        );
+        assert_eq!(
+            query.bucket_starts(),
+            vec![
+                utc("2025-07-31T15:00:00Z"),
+                utc("2025-08-01T15:00:00Z"),
+                utc("2025-08-02T15:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weeks_start_on_monday() {
+        let query = query(
+            StatsGranularity::Week,
+            "2025-08-06T00:00:00Z",
+            "2025-08-07T00:00:00Z",
+        );
+
+        // 2025-08-06は水曜日、その週は日本時間の8月4日（月）から
+        assert_eq!(query.bucket_starts(), vec![utc("2025-08-03T15:00:00Z")]);
+    }
+
+    #[test]
+    fn test_hours_and_daylight_saving_time() {
+        let hours = query(
+            StatsGranularity::Hour,
+            "2025-08-01T00:30:00Z",
+            "2025-08-01T03:00:00Z",
+        );
+        assert_eq!(hours.bucket_starts().len(), 3);
+
+        // 夏時間が終わる日（25時間）も1日は1つの区切り
+        let new_york = StatsQuery {
+            timezone: chrono_tz::America::New_York,
+            ..query(
+                StatsGranularity::Day,
+                "2025-11-02T04:00:00Z",
+                "2025-11-04T05:00:00Z",
+            )
+        };
+        assert_eq!(
+            new_york.bucket_starts(),
+            vec![utc("2025-11-02T04:00:00Z"), utc("2025-11-03T05:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut q = query(
+            StatsGranularity::Hour,
+            "2025-01-01T00:00:00Z",
+            "2025-03-01T00:00:00Z",
+        );
+        assert!(q.validate().is_err());
+
+        q.granularity = StatsGranularity::Day;
+        assert!(q.validate().is_ok());
+
+        q.to = q.from;
+        assert!(q.validate().is_err());
+    }
+
+    #[test]
+    fn test_assemble_buckets_fills_empty_buckets() {
+        let query = query(
+            StatsGranularity::Day,
+            "2025-07-31T15:00:00Z",
+            "2025-08-02T15:00:00Z",
+        );
+        let day1 = utc("2025-07-31T15:00:00Z");
+
+        let buckets = assemble_buckets(
+            &query,
+            vec![(day1, 3)],
+            vec![
+                (day1, "Signing".to_string(), 1),
+                (day1, "Trade".to_string(), 2),
+            ],
+        );
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].count, 3);
+        assert_eq!(buckets[0].groups[0].key, "Trade");
+        assert_eq!(buckets[1].count, 0);
+        assert!(buckets[1].groups.is_empty());
+    }
+}
@@ -0,0 +1,31 @@
+//! テスト用のデータ
+//!
+//! 各テストは[`news_item`]を元に、必要なフィールドだけを上書きして使います。
+//!
+//! ```ignore
+//! let item = NewsItem {
+//!     category: "Signing".to_string(),
+//!     ..news_item("id-1", "Lakers acquire guard")
+//! };
+//! ```
+
+use chrono::Utc;
+
+use crate::scraper::{NewsItem, NewsSource};
+
+/// テスト用のニュース
+///
+/// リンクはIDから作り、カテゴリーはスクレイパーと同じくタイトルから判定します。
+pub fn news_item(id: &str, title: &str) -> NewsItem {
+    NewsItem {
+        id: id.to_string(),
+        title: title.to_string(),
+        description: Some("Test description".to_string()),
+        link: format!("https://example.com/{id}"),
+        source: NewsSource::ESPN,
+        category: NewsItem::determine_category(title, None),
+        published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
+    }
+}
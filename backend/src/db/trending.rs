@@ -0,0 +1,292 @@
+//! 話題のチーム・選手のランキング
+//!
+//! 直近の集計期間（6時間・24時間・7日）の言及数を、その前の28日間から見込まれる
+//! 言及数と比べて「勢い」を求めます。言及は記事と関連チーム・選手の関係から数え、
+//! カテゴリーで重み付けします（トレード3、契約2、その他1）。
+//!
+//! 計算はスケジューラーのジョブで定期的に行い、結果をリポジトリに保存します。
+//! `trending`クエリは保存済みの結果を返すため、リクエストごとに集計しません
+//! （まだ計算されていないか、ジョブが止まって古くなった場合だけその場で計算します）。
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::db::repository::NewsRepository;
+
+/// 保存するランキングの最大件数
+pub const MAX_TRENDING_ENTRIES: usize = 100;
+
+/// 比較の基準にする、集計期間の直前の日数
+pub const BASELINE_DAYS: i64 = 28;
+
+/// 保存済みのランキングをそのまま返す期間（スケジューラーの間隔の3倍）
+pub const MAX_SNAPSHOT_AGE_MINUTES: i64 = 30;
+
+/// 見込みの言及数に足す数（言及の少ないチーム・選手の勢いが極端に大きくならないように）
+const SMOOTHING: f64 = 1.0;
+
+/// ランキングの集計期間
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrendingWindow {
+    SixHours,
+    Day,
+    Week,
+}
+
+impl TrendingWindow {
+    /// スケジューラーが計算するすべての集計期間
+    pub const ALL: [TrendingWindow; 3] = [
+        TrendingWindow::SixHours,
+        TrendingWindow::Day,
+        TrendingWindow::Week,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrendingWindow::SixHours => "6h",
+            TrendingWindow::Day => "24h",
+            TrendingWindow::Week => "7d",
+        }
+    }
+
+    pub fn duration(self) -> Duration {
+        match self {
+            TrendingWindow::SixHours => Duration::hours(6),
+            TrendingWindow::Day => Duration::days(1),
+            TrendingWindow::Week => Duration::days(7),
+        }
+    }
+}
+
+/// ランキングの対象の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrendingKind {
+    Team,
+    Player,
+}
+
+impl TrendingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrendingKind::Team => "team",
+            TrendingKind::Player => "player",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "team" => Some(TrendingKind::Team),
+            "player" => Some(TrendingKind::Player),
+            _ => None,
+        }
+    }
+}
+
+/// カテゴリーごとの言及の重み
+pub fn category_weight(category: &str) -> f64 {
+    match category {
+        "Trade" => 3.0,
+        "Signing" => 2.0,
+        _ => 1.0,
+    }
+}
+
+/// チーム・選手とカテゴリーごとの言及数
+#[derive(Debug, Clone, PartialEq)]
+pub struct MentionCount {
+    pub kind: TrendingKind,
+    pub id: String,
+    pub name: String,
+    pub name_ja: Option<String>,
+    pub category: String,
+    /// 集計期間内の言及数
+    pub in_window: i64,
+    /// 基準期間内の言及数
+    pub in_baseline: i64,
+}
+
+/// ランキングの1件
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendingEntry {
+    pub kind: TrendingKind,
+    pub id: String,
+    pub name: String,
+    pub name_ja: Option<String>,
+    /// 集計期間内に言及した記事の数
+    pub mentions: i64,
+    /// カテゴリーで重み付けした言及数
+    pub weighted_mentions: f64,
+    /// 基準期間から見込まれる、集計期間と同じ長さあたりの重み付き言及数
+    pub baseline: f64,
+    /// 勢い（`weighted_mentions / (baseline + 1)`）
+    pub score: f64,
+}
+
+/// ある時点で計算したランキング
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendingSnapshot {
+    pub window: TrendingWindow,
+    pub computed_at: DateTime<Utc>,
+    /// 勢いの大きい順
+    pub entries: Vec<TrendingEntry>,
+}
+
+/// 言及数から勢いを求め、大きい順に並べる（集計期間内に言及のないものは除く）
+pub fn rank_mentions(
+    window: TrendingWindow,
+    counts: impl IntoIterator<Item = MentionCount>,
+) -> Vec<TrendingEntry> {
+    let scale =
+        window.duration().num_seconds() as f64 / Duration::days(BASELINE_DAYS).num_seconds() as f64;
+
+    let mut entries: HashMap<(TrendingKind, String), TrendingEntry> = HashMap::new();
+    for count in counts {
+        let weight = category_weight(&count.category);
+        let entry = entries
+            .entry((count.kind, count.id.clone()))
+            .or_insert_with(|| TrendingEntry {
+                kind: count.kind,
+                id: count.id,
+                name: count.name,
+                name_ja: count.name_ja,
+                mentions: 0,
+                weighted_mentions: 0.0,
+                baseline: 0.0,
+                score: 0.0,
+            });
+        entry.mentions += count.in_window;
+        entry.weighted_mentions += weight * count.in_window as f64;
+        entry.baseline += weight * count.in_baseline as f64 * scale;
+    }
+
+    let mut entries: Vec<TrendingEntry> = entries
+        .into_values()
+        .filter(|entry| entry.mentions > 0)
+        .map(|entry| TrendingEntry {
+            score: entry.weighted_mentions / (entry.baseline + SMOOTHING),
+            ..entry
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.mentions.cmp(&a.mentions))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    entries.truncate(MAX_TRENDING_ENTRIES);
+    entries
+}
+
+/// `now`時点のランキングを計算して保存する
+pub async fn refresh_trending(
+    repository: &dyn NewsRepository,
+    window: TrendingWindow,
+    now: DateTime<Utc>,
+) -> Result<TrendingSnapshot> {
+    let window_start = now - window.duration();
+    let baseline_start = window_start - Duration::days(BASELINE_DAYS);
+    let counts = repository
+        .mention_counts(baseline_start, window_start, now)
+        .await?;
+
+    let snapshot = TrendingSnapshot {
+        window,
+        computed_at: now,
+        entries: rank_mentions(window, counts),
+    };
+    repository.save_trending(&snapshot).await?;
+    Ok(snapshot)
+}
+
+/// 保存済みのランキングを返す（なければ、または古ければ計算し直す）
+///
+/// 計算し直せなかった場合は、古いランキングがあればそれを返します。
+pub async fn current_trending(
+    repository: &dyn NewsRepository,
+    window: TrendingWindow,
+    now: DateTime<Utc>,
+) -> Result<TrendingSnapshot> {
+    let max_age = Duration::minutes(MAX_SNAPSHOT_AGE_MINUTES);
+    match repository.get_trending(window).await? {
+        Some(snapshot) if now - snapshot.computed_at <= max_age => Ok(snapshot),
+        cached => match refresh_trending(repository, window, now).await {
+            Ok(snapshot) => Ok(snapshot),
+            Err(e) => {
+                let Some(stale) = cached else {
+                    return Err(e);
+                };
+                warn!(
+                    "Failed to refresh trending ranking {}: {}",
+                    window.as_str(),
+                    e
+                );
+                Ok(stale)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(
+        kind: TrendingKind,
+        id: &str,
+        category: &str,
+        window: i64,
+        baseline: i64,
+    ) -> MentionCount {
+        MentionCount {
+            kind,
+            id: id.to_string(),
+            name: id.to_string(),
+            name_ja: None,
+            category: category.to_string(),
+            in_window: window,
+            in_baseline: baseline,
+        }
+    }
+
+    #[test]
+    fn test_rank_mentions_compares_with_baseline() {
+        let entries = rank_mentions(
+            TrendingWindow::Week,
+            vec![
+                // いつも話題のチーム（28日間で40件 = 1週間あたり10件）
+                count(TrendingKind::Team, "LAL", "Other", 10, 40),
+                // 急に話題になった選手
+                count(TrendingKind::Player, "lebron-james", "Other", 4, 0),
+                // 基準期間にしか言及がない
+                count(TrendingKind::Team, "BOS", "Trade", 0, 12),
+            ],
+        );
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "lebron-james");
+        assert_eq!(entries[0].score, 4.0);
+        assert_eq!(entries[1].id, "LAL");
+        assert_eq!(entries[1].baseline, 10.0);
+        assert_eq!(entries[1].score, 10.0 / 11.0);
+    }
+
+    #[test]
+    fn test_rank_mentions_weights_categories() {
+        let entries = rank_mentions(
+            TrendingWindow::Day,
+            vec![
+                count(TrendingKind::Team, "MIA", "Other", 2, 0),
+                count(TrendingKind::Team, "DAL", "Trade", 1, 0),
+                count(TrendingKind::Team, "DAL", "Signing", 1, 0),
+            ],
+        );
+
+        assert_eq!(entries[0].id, "DAL");
+        assert_eq!(entries[0].mentions, 2);
+        assert_eq!(entries[0].weighted_mentions, 5.0);
+        assert_eq!(entries[1].id, "MIA");
+        assert_eq!(entries[1].weighted_mentions, 2.0);
+    }
+}
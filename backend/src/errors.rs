@@ -0,0 +1,405 @@
+//! エラーの種類とGraphQLのエラーコード
+//!
+//! リゾルバーの失敗はGraphQLのエラーの`extensions.code`に、スクレイピング・翻訳で
+//! 記事・フィードごとに起きたエラーは[`ItemError`]の`code`に、同じ[`ErrorCode`]で
+//! 表します。クライアントはメッセージの文字列ではなくコードで処理を分けられます
+//! （レート制限なら待って再試行する、認証エラーならログインし直す、など）。
+//! REST API（[`crate::rest`]）も同じコードを返し、HTTPステータスは
+//! [`ErrorCode::http_status`]で決めます。
+//!
+//! リゾルバーが`anyhow`のエラーをそのまま返しても、[`ErrorCodes`]拡張が原因を
+//! たどってコードを付けます（データベースに接続できなければ`DATABASE_UNAVAILABLE`）。
+
+use std::sync::Arc;
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextRequest, NextSubscribe,
+};
+use async_graphql::futures_util::stream::BoxStream;
+use async_graphql::futures_util::StreamExt;
+use async_graphql::{ErrorExtensions, Response, ServerError};
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::services::TranslationError;
+
+/// エラーの種類を表すコード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// RSSフィードを取得できなかった
+    FeedFetchFailed,
+    /// RSSフィードを解析できなかった
+    FeedParseFailed,
+    /// IDやリンクのない記事
+    InvalidItem,
+    /// 一意であるべき値が重複している
+    Duplicate,
+    TranslationFailed,
+    /// 翻訳サービスのレート制限に達した
+    TranslationRateLimited,
+    /// データベースに接続できない
+    DatabaseUnavailable,
+    Unauthenticated,
+    /// ロールが足りない
+    Forbidden,
+    /// リクエストが多すぎる
+    RateLimited,
+    NotFound,
+    /// 引数やクエリが正しくない
+    InvalidInput,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 13] = [
+        ErrorCode::FeedFetchFailed,
+        ErrorCode::FeedParseFailed,
+        ErrorCode::InvalidItem,
+        ErrorCode::Duplicate,
+        ErrorCode::TranslationFailed,
+        ErrorCode::TranslationRateLimited,
+        ErrorCode::DatabaseUnavailable,
+        ErrorCode::Unauthenticated,
+        ErrorCode::Forbidden,
+        ErrorCode::RateLimited,
+        ErrorCode::NotFound,
+        ErrorCode::InvalidInput,
+        ErrorCode::Internal,
+    ];
+
+    /// `extensions.code`に入れる値
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::FeedFetchFailed => "FEED_FETCH_FAILED",
+            ErrorCode::FeedParseFailed => "FEED_PARSE_FAILED",
+            ErrorCode::InvalidItem => "INVALID_ITEM",
+            ErrorCode::Duplicate => "DUPLICATE",
+            ErrorCode::TranslationFailed => "TRANSLATION_FAILED",
+            ErrorCode::TranslationRateLimited => "TRANSLATION_RATE_LIMITED",
+            ErrorCode::DatabaseUnavailable => "DATABASE_UNAVAILABLE",
+            ErrorCode::Unauthenticated => "UNAUTHENTICATED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::InvalidInput => "INVALID_INPUT",
+            ErrorCode::Internal => "INTERNAL_SERVER_ERROR",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|code| code.as_str() == value)
+    }
+
+    /// REST APIで返すHTTPステータス
+    pub fn http_status(self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidItem | ErrorCode::InvalidInput => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthenticated => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Duplicate => StatusCode::CONFLICT,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::FeedFetchFailed
+            | ErrorCode::FeedParseFailed
+            | ErrorCode::TranslationFailed
+            | ErrorCode::TranslationRateLimited => StatusCode::BAD_GATEWAY,
+            ErrorCode::DatabaseUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// 記事・フィードごとのエラーが起きた処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorStage {
+    /// フィードの取得
+    Fetch,
+    /// フィードの解析
+    Parse,
+    /// 保存前の検証
+    Validate,
+    /// リポジトリへの保存
+    Save,
+    /// タイトルの翻訳（失敗した記事は保存しない）
+    TranslateTitle,
+    /// 説明文の翻訳（失敗してもタイトルの翻訳は保存する）
+    TranslateDescription,
+}
+
+impl ErrorStage {
+    pub const ALL: [ErrorStage; 6] = [
+        ErrorStage::Fetch,
+        ErrorStage::Parse,
+        ErrorStage::Validate,
+        ErrorStage::Save,
+        ErrorStage::TranslateTitle,
+        ErrorStage::TranslateDescription,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorStage::Fetch => "fetch",
+            ErrorStage::Parse => "parse",
+            ErrorStage::Validate => "validate",
+            ErrorStage::Save => "save",
+            ErrorStage::TranslateTitle => "translate_title",
+            ErrorStage::TranslateDescription => "translate_description",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.as_str() == value)
+    }
+}
+
+/// 記事・フィードごとのエラー（ジョブは続行する）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemError {
+    /// 記事のID（フィードの取得・解析のエラーではフィードのソース名）
+    pub item_id: String,
+    pub stage: ErrorStage,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ItemError {
+    pub fn new(
+        item_id: impl Into<String>,
+        stage: ErrorStage,
+        code: ErrorCode,
+        message: impl std::fmt::Display,
+    ) -> Self {
+        Self {
+            item_id: item_id.into(),
+            stage,
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// アプリケーションのエラー
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Failed to fetch feed: {0}")]
+    FeedFetch(String),
+    #[error("Failed to parse feed: {0}")]
+    FeedParse(String),
+    #[error("Invalid news item: {0}")]
+    InvalidItem(String),
+    #[error("Duplicate value: {0}")]
+    Duplicate(String),
+    #[error("Translation failed: {0}")]
+    TranslationFailed(String),
+    #[error("Translation rate limit exceeded")]
+    TranslationRateLimited,
+    #[error("Database is unavailable: {0}")]
+    DatabaseUnavailable(String),
+    #[error("Authentication is required")]
+    Unauthenticated,
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::FeedFetch(_) => ErrorCode::FeedFetchFailed,
+            AppError::FeedParse(_) => ErrorCode::FeedParseFailed,
+            AppError::InvalidItem(_) => ErrorCode::InvalidItem,
+            AppError::Duplicate(_) => ErrorCode::Duplicate,
+            AppError::TranslationFailed(_) => ErrorCode::TranslationFailed,
+            AppError::TranslationRateLimited => ErrorCode::TranslationRateLimited,
+            AppError::DatabaseUnavailable(_) => ErrorCode::DatabaseUnavailable,
+            AppError::Unauthenticated => ErrorCode::Unauthenticated,
+            AppError::Forbidden(_) => ErrorCode::Forbidden,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::InvalidInput(_) => ErrorCode::InvalidInput,
+            AppError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// 検証エラーを引数の誤りとして返す（`map_err(AppError::invalid_input)`）
+    pub fn invalid_input(error: impl std::fmt::Display) -> Self {
+        AppError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<TranslationError> for AppError {
+    fn from(error: TranslationError) -> Self {
+        match error {
+            TranslationError::RateLimitExceeded => AppError::TranslationRateLimited,
+            error => AppError::TranslationFailed(error.to_string()),
+        }
+    }
+}
+
+impl ErrorExtensions for AppError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string())
+            .extend_with(|_, e| e.set("code", self.code().as_str()))
+    }
+}
+
+/// `anyhow`のエラーの種類（原因をたどって判定し、分からなければ`Internal`）
+pub fn error_code(error: &anyhow::Error) -> ErrorCode {
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(app) = cause.downcast_ref::<AppError>() {
+                return Some(app.code());
+            }
+            if let Some(sqlx) = cause.downcast_ref::<sqlx::Error>() {
+                return Some(sqlx_error_code(sqlx));
+            }
+            cause
+                .downcast_ref::<TranslationError>()
+                .map(translation_error_code)
+        })
+        .unwrap_or(ErrorCode::Internal)
+}
+
+fn sqlx_error_code(error: &sqlx::Error) -> ErrorCode {
+    match error {
+        sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_) => ErrorCode::DatabaseUnavailable,
+        sqlx::Error::Database(db) if db.is_unique_violation() => ErrorCode::Duplicate,
+        _ => ErrorCode::Internal,
+    }
+}
+
+pub(crate) fn translation_error_code(error: &TranslationError) -> ErrorCode {
+    match error {
+        TranslationError::RateLimitExceeded => ErrorCode::TranslationRateLimited,
+        _ => ErrorCode::TranslationFailed,
+    }
+}
+
+/// GraphQLのエラーの種類
+///
+/// リゾルバーが`?`で返したエラーは原因を保持しているため、その種類で判定します。
+/// 原因のないエラーはクエリの構文・検証・引数の誤りです。
+pub fn server_error_code(error: &ServerError) -> ErrorCode {
+    if let Some(app) = error.source::<AppError>() {
+        app.code()
+    } else if let Some(error) = error.source::<anyhow::Error>() {
+        error_code(error)
+    } else if let Some(sqlx) = error.source::<sqlx::Error>() {
+        sqlx_error_code(sqlx)
+    } else if let Some(translation) = error.source::<TranslationError>() {
+        translation_error_code(translation)
+    } else if error.source.is_some() {
+        ErrorCode::Internal
+    } else {
+        ErrorCode::InvalidInput
+    }
+}
+
+/// `extensions.code`のないエラーにコードを付ける
+fn set_code(response: &mut Response) {
+    for error in &mut response.errors {
+        let has_code = error
+            .extensions
+            .as_ref()
+            .is_some_and(|extensions| extensions.get("code").is_some());
+        if !has_code {
+            let code = server_error_code(error);
+            error
+                .extensions
+                .get_or_insert_with(Default::default)
+                .set("code", code.as_str());
+        }
+    }
+}
+
+/// すべてのGraphQLのエラーに`extensions.code`を付ける拡張
+pub struct ErrorCodes;
+
+impl ExtensionFactory for ErrorCodes {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ErrorCodes)
+    }
+}
+
+#[async_trait]
+impl Extension for ErrorCodes {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let mut response = next.run(ctx).await;
+        set_code(&mut response);
+        response
+    }
+
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        next.run(ctx, stream)
+            .map(|mut response| {
+                set_code(&mut response);
+                response
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_error_code_follows_causes() {
+        let fetch: anyhow::Result<()> = Err(AppError::FeedFetch("timed out".to_string()).into());
+        let error = fetch.context("ESPN").unwrap_err();
+        assert_eq!(error_code(&error), ErrorCode::FeedFetchFailed);
+
+        let error = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert_eq!(error_code(&error), ErrorCode::DatabaseUnavailable);
+
+        let error = anyhow::Error::from(TranslationError::RateLimitExceeded);
+        assert_eq!(error_code(&error), ErrorCode::TranslationRateLimited);
+
+        assert_eq!(
+            error_code(&anyhow::anyhow!("something went wrong")),
+            ErrorCode::Internal
+        );
+    }
+
+    #[test]
+    fn test_server_error_code() {
+        let error = |e: async_graphql::Error| e.into_server_error(Default::default());
+
+        let not_found = AppError::NotFound("News not found: x".to_string());
+        assert_eq!(
+            server_error_code(&error(not_found.into())),
+            ErrorCode::NotFound
+        );
+        let db: async_graphql::Error = anyhow::Error::from(sqlx::Error::PoolClosed).into();
+        assert_eq!(
+            server_error_code(&error(db)),
+            ErrorCode::DatabaseUnavailable
+        );
+        assert_eq!(
+            server_error_code(&error(async_graphql::Error::new("bad argument"))),
+            ErrorCode::InvalidInput
+        );
+
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::parse(code.as_str()), Some(code));
+        }
+        for stage in ErrorStage::ALL {
+            assert_eq!(ErrorStage::parse(stage.as_str()), Some(stage));
+        }
+    }
+}
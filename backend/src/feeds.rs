@@ -0,0 +1,633 @@
+//! RSS・Atomフィードの配信
+//!
+//! 保存済みの記事から、フィードリーダー向けのRSS 2.0・Atomフィードを作ります。
+//! パスで言語・対象・形式を選びます（拡張子`.xml`・`.rss`はRSS、`.atom`はAtom）。
+//!
+//! - `/feeds/news.xml`: すべての記事
+//! - `/feeds/trades.xml`・`/feeds/signings.xml`: トレード・契約の記事
+//! - `/feeds/official.xml`: 公式発表
+//! - `/feeds/team/{code}.atom`: チーム（例: `LAL`）の関連記事
+//! - `/feeds/ja/...`: 上記の日本語版（翻訳が終わった記事だけを`title_ja`・
+//!   `description_ja`で配信）
+//!
+//! 各フィードは新しい順に最大[`FEED_SIZE`]件です。記事のGUID（Atomの`id`）は記事ID
+//! と言語から作るため、再スクレイピングや翻訳で内容が変わっても同じ記事として扱われます。
+//!
+//! `lastBuildDate`（Atomの`updated`）は記事が最後に保存・翻訳された日時です。
+//! レスポンスには`ETag`と`Last-Modified`を付け、`If-None-Match`・`If-Modified-Since`
+//! が一致すれば`304 Not Modified`を返します。
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+
+use crate::db::{content_version, fetch_news_page, models, NewsFilter, SharedNewsRepository};
+use crate::errors::AppError;
+use crate::graphql;
+use crate::http_cache::{etag, etag_matches};
+use crate::locale::{self, Locale};
+use crate::rest::ApiError;
+use crate::utils::env_utils::env_parse;
+
+/// フィードのパス
+pub const FEEDS_PATH: &str = "/feeds";
+
+/// 1つのフィードに入れる記事の数
+pub const FEED_SIZE: usize = 50;
+
+/// `FEED_BASE_URL`が未設定のときの公開URL
+const DEFAULT_BASE_URL: &str = "http://localhost:8000";
+
+/// フィードの設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedConfig {
+    /// サーバーの公開URL（フィード自身のURLに使う。末尾の`/`なし）
+    pub base_url: String,
+    /// `Cache-Control`の`max-age`（秒）
+    pub max_age: u64,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            max_age: 300,
+        }
+    }
+}
+
+impl FeedConfig {
+    /// 環境変数から設定を読み込む
+    ///
+    /// - `FEED_BASE_URL`: サーバーの公開URL（デフォルトは`http://localhost:8000`）
+    /// - `FEED_CACHE_MAX_AGE`: `Cache-Control`の`max-age`（秒、デフォルトは300）
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            base_url: std::env::var("FEED_BASE_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or(defaults.base_url),
+            max_age: env_parse("FEED_CACHE_MAX_AGE").unwrap_or(defaults.max_age),
+        }
+    }
+}
+
+/// フィードのルーター（[`FEEDS_PATH`]の下に置く）
+pub fn routes(repository: SharedNewsRepository, config: FeedConfig) -> Router {
+    Router::new()
+        .route("/{*path}", get(feed))
+        .with_state(FeedState { repository, config })
+}
+
+#[derive(Clone)]
+struct FeedState {
+    repository: SharedNewsRepository,
+    config: FeedConfig,
+}
+
+/// フィードの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+}
+
+impl FeedFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+        }
+    }
+}
+
+/// フィードに入れる記事
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedTopic {
+    /// すべての記事
+    News,
+    Trades,
+    Signings,
+    /// 公式発表
+    Official,
+    /// チーム（ID、例: LAL）の関連記事
+    Team(String),
+}
+
+/// パスから読み取ったフィードの種類
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedSpec {
+    pub locale: Locale,
+    pub topic: FeedTopic,
+    pub format: FeedFormat,
+}
+
+impl FeedSpec {
+    /// `/feeds`より後ろのパス（例: `ja/team/lal.atom`）を読み取る
+    pub fn parse(path: &str) -> Option<Self> {
+        let mut segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        let locale = match segments.first() {
+            Some(&"ja") => {
+                segments.remove(0);
+                Locale::Ja
+            }
+            _ => Locale::En,
+        };
+        let (stem, extension) = segments.last()?.rsplit_once('.')?;
+        let format = match extension {
+            "xml" | "rss" => FeedFormat::Rss,
+            "atom" => FeedFormat::Atom,
+            _ => return None,
+        };
+        let topic = match segments[..segments.len() - 1] {
+            [] => match stem {
+                "news" => FeedTopic::News,
+                "trades" => FeedTopic::Trades,
+                "signings" => FeedTopic::Signings,
+                "official" => FeedTopic::Official,
+                _ => return None,
+            },
+            ["team"] if !stem.is_empty() => FeedTopic::Team(stem.to_uppercase()),
+            _ => return None,
+        };
+
+        Some(Self {
+            locale,
+            topic,
+            format,
+        })
+    }
+
+    /// フィード自身のパス（`/feeds`から）
+    pub fn path(&self) -> String {
+        let language = match self.locale {
+            Locale::En => "",
+            Locale::Ja => "/ja",
+        };
+        let name = match &self.topic {
+            FeedTopic::News => "news".to_string(),
+            FeedTopic::Trades => "trades".to_string(),
+            FeedTopic::Signings => "signings".to_string(),
+            FeedTopic::Official => "official".to_string(),
+            FeedTopic::Team(code) => format!("team/{}", code.to_lowercase()),
+        };
+        let extension = match self.format {
+            FeedFormat::Rss => "xml",
+            FeedFormat::Atom => "atom",
+        };
+        format!("{}{}/{}.{}", FEEDS_PATH, language, name, extension)
+    }
+
+    /// 記事の絞り込み条件（日本語版は翻訳が終わった記事だけ）
+    fn filter(&self) -> NewsFilter {
+        let mut filter = NewsFilter::default();
+        match &self.topic {
+            FeedTopic::News => {}
+            FeedTopic::Trades => filter.category = Some("Trade".to_string()),
+            FeedTopic::Signings => filter.category = Some("Signing".to_string()),
+            FeedTopic::Official => filter.is_official = Some(true),
+            FeedTopic::Team(code) => filter.team_id = Some(code.clone()),
+        }
+        if self.locale == Locale::Ja {
+            filter.translation_status = Some("completed".to_string());
+        }
+        filter
+    }
+
+    /// フィードのタイトル
+    fn title(&self, team: Option<&models::Team>) -> String {
+        let site = match self.locale {
+            Locale::En => "NBA Trade News",
+            Locale::Ja => "NBAトレードニュース",
+        };
+        let topic = match (&self.topic, self.locale) {
+            (FeedTopic::News, _) => return site.to_string(),
+            (FeedTopic::Trades, _) => locale::category_label("Trade", self.locale).to_string(),
+            (FeedTopic::Signings, Locale::En) => "Signings".to_string(),
+            (FeedTopic::Signings, Locale::Ja) => {
+                locale::category_label("Signing", self.locale).to_string()
+            }
+            (FeedTopic::Official, Locale::En) => "Official Announcements".to_string(),
+            (FeedTopic::Official, Locale::Ja) => "公式発表".to_string(),
+            (FeedTopic::Team(code), _) => team.map_or_else(
+                || code.clone(),
+                |team| {
+                    let full_name = format!("{} {}", team.city, team.name);
+                    locale::localize(self.locale, &full_name, team.name_ja.as_deref())
+                        .0
+                        .to_string()
+                },
+            ),
+        };
+        format!("{}: {}", site, topic)
+    }
+}
+
+/// フィードに載せる1件
+struct FeedEntry {
+    guid: String,
+    title: String,
+    description: Option<String>,
+    link: String,
+    category: String,
+    published_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl FeedEntry {
+    fn new(item: models::TradeNews, locale: Locale) -> Self {
+        let updated_at = match locale {
+            Locale::En => item.published_at,
+            Locale::Ja => item.translated_at.unwrap_or(item.published_at),
+        }
+        .max(item.published_at);
+        // HTMLタグの除去はGraphQLと同じ
+        let news = graphql::TradeNews::from(item);
+        let (title, _) = locale::localize(locale, &news.title, news.title_ja.as_deref());
+        let description = news.description.as_deref().map(|description| {
+            locale::localize(locale, description, news.description_ja.as_deref())
+                .0
+                .to_string()
+        });
+
+        FeedEntry {
+            guid: guid(&news.id, locale),
+            title: title.to_string(),
+            description,
+            link: news.link,
+            category: locale::category_label(&news.category, locale).to_string(),
+            published_at: news.published_at,
+            updated_at,
+        }
+    }
+}
+
+/// 記事のGUID（記事IDと言語から作る、変わらない値）
+fn guid(id: &str, locale: Locale) -> String {
+    let id: String = url::form_urlencoded::byte_serialize(id.as_bytes()).collect();
+    format!("urn:nba-trade-news:{}:{}", locale.as_str(), id)
+}
+
+/// HTTPの日時の形式（例: `Sun, 06 Nov 1994 08:49:37 GMT`）
+fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// `If-None-Match`、なければ`If-Modified-Since`で、クライアントのフィードが最新か判定する
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: Option<DateTime<Utc>>) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match
+            .to_str()
+            .is_ok_and(|value| etag_matches(value, etag));
+    }
+
+    let since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok());
+    match (since, last_modified) {
+        // HTTPの日時は秒単位のため、秒未満を切り捨てて比べる
+        (Some(since), Some(last_modified)) => last_modified.timestamp() <= since.timestamp(),
+        _ => false,
+    }
+}
+
+/// フィードを返す
+async fn feed(
+    State(state): State<FeedState>,
+    Path(path): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let spec = FeedSpec::parse(&path)
+        .ok_or_else(|| AppError::NotFound(format!("Feed not found: {}", path)))?;
+    let team = match &spec.topic {
+        FeedTopic::Team(code) => Some(
+            state
+                .repository
+                .get_team(code)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Team not found: {}", code)))?,
+        ),
+        _ => None,
+    };
+
+    let version = content_version(state.repository.as_ref()).await?;
+    let etag = etag(&version, &spec.path(), None);
+    let cache_headers = [
+        (
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", state.config.max_age),
+        ),
+        (header::ETAG, etag.clone()),
+        (
+            header::LAST_MODIFIED,
+            version.last_modified.map(http_date).unwrap_or_default(),
+        ),
+    ];
+    let mut response = if is_not_modified(&headers, &etag, version.last_modified) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let page = fetch_news_page(
+            state.repository.as_ref(),
+            &spec.filter(),
+            None,
+            None,
+            Some(FEED_SIZE),
+            None,
+        )
+        .await?;
+        let entries: Vec<FeedEntry> = page
+            .items
+            .into_iter()
+            .map(|item| FeedEntry::new(item, spec.locale))
+            .collect();
+        let updated = version.last_modified.unwrap_or_else(Utc::now);
+        let body = match spec.format {
+            FeedFormat::Rss => rss_feed(&state.config, &spec, team.as_ref(), &entries, updated),
+            FeedFormat::Atom => atom_feed(&state.config, &spec, team.as_ref(), &entries, updated),
+        };
+        ([(header::CONTENT_TYPE, spec.format.content_type())], body).into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    for (name, value) in cache_headers {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            if !value.is_empty() {
+                response_headers.insert(name, value);
+            }
+        }
+    }
+    Ok(response)
+}
+
+fn rss_feed(
+    config: &FeedConfig,
+    spec: &FeedSpec,
+    team: Option<&models::Team>,
+    entries: &[FeedEntry],
+    updated: DateTime<Utc>,
+) -> String {
+    let items = entries
+        .iter()
+        .map(|entry| rss::Item {
+            title: Some(entry.title.clone()),
+            link: Some(entry.link.clone()),
+            description: entry.description.clone(),
+            guid: Some(rss::Guid {
+                value: entry.guid.clone(),
+                permalink: false,
+            }),
+            pub_date: Some(entry.published_at.to_rfc2822()),
+            categories: vec![rss::Category {
+                name: entry.category.clone(),
+                domain: None,
+            }],
+            ..rss::Item::default()
+        })
+        .collect();
+
+    rss::Channel {
+        title: spec.title(team),
+        link: format!("{}{}", config.base_url, spec.path()),
+        description: spec.title(team),
+        language: Some(spec.locale.as_str().to_string()),
+        last_build_date: Some(updated.to_rfc2822()),
+        ttl: Some((config.max_age / 60).max(1).to_string()),
+        items,
+        ..rss::Channel::default()
+    }
+    .to_string()
+}
+
+fn atom_feed(
+    config: &FeedConfig,
+    spec: &FeedSpec,
+    team: Option<&models::Team>,
+    entries: &[FeedEntry],
+    updated: DateTime<Utc>,
+) -> String {
+    let url = format!("{}{}", config.base_url, spec.path());
+    let entries = entries
+        .iter()
+        .map(|entry| atom_syndication::Entry {
+            title: entry.title.clone().into(),
+            id: entry.guid.clone(),
+            updated: entry.updated_at.fixed_offset(),
+            published: Some(entry.published_at.fixed_offset()),
+            links: vec![atom_syndication::Link {
+                href: entry.link.clone(),
+                ..atom_syndication::Link::default()
+            }],
+            summary: entry.description.clone().map(Into::into),
+            categories: vec![atom_syndication::Category {
+                term: entry.category.clone(),
+                ..atom_syndication::Category::default()
+            }],
+            ..atom_syndication::Entry::default()
+        })
+        .collect();
+
+    atom_syndication::Feed {
+        title: spec.title(team).into(),
+        id: url.clone(),
+        updated: updated.fixed_offset(),
+        authors: vec![atom_syndication::Person {
+            name: "NBA Trade News".to_string(),
+            ..atom_syndication::Person::default()
+        }],
+        links: vec![atom_syndication::Link {
+            href: url,
+            rel: "self".to_string(),
+            mime_type: Some("application/atom+xml".to_string()),
+            ..atom_syndication::Link::default()
+        }],
+        lang: Some(spec.locale.as_str().to_string()),
+        entries,
+        ..atom_syndication::Feed::default()
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::news_item;
+    use crate::db::{InMemoryNewsStore, NewsRepository};
+    use crate::scraper::NewsItem;
+    use std::sync::Arc;
+    use tower::util::ServiceExt;
+
+    #[test]
+    fn test_parse_feed_paths() {
+        let spec = FeedSpec::parse("trades.xml").unwrap();
+        assert_eq!(spec.locale, Locale::En);
+        assert_eq!(spec.topic, FeedTopic::Trades);
+        assert_eq!(spec.format, FeedFormat::Rss);
+
+        let spec = FeedSpec::parse("ja/team/lal.atom").unwrap();
+        assert_eq!(spec.locale, Locale::Ja);
+        assert_eq!(spec.topic, FeedTopic::Team("LAL".to_string()));
+        assert_eq!(spec.format, FeedFormat::Atom);
+        assert_eq!(spec.path(), "/feeds/ja/team/lal.atom");
+
+        for path in [
+            "trades.json",
+            "unknown.xml",
+            "team/.xml",
+            "en/news.xml",
+            "team",
+        ] {
+            assert!(FeedSpec::parse(path).is_none(), "{}", path);
+        }
+    }
+
+    async fn test_routes() -> (Arc<InMemoryNewsStore>, Router) {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![
+                NewsItem {
+                    description: Some("<p>Details</p>".to_string()),
+                    ..news_item("feed-1", "Lakers acquire guard")
+                },
+                news_item("feed-2", "Celtics sign forward"),
+                news_item("feed-3", "Lakers trade center"),
+            ])
+            .await
+            .unwrap();
+        store
+            .save_translation("feed-1", "レイカーズがガードを獲得", Some("詳細"))
+            .await
+            .unwrap();
+        let app = routes(store.clone(), FeedConfig::default());
+        (store, app)
+    }
+
+    async fn get(app: &Router, uri: &str, headers: &[(&str, &str)]) -> Response {
+        let mut request = axum::http::Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        app.clone()
+            .oneshot(request.body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_rss_feed_applies_filters() {
+        let (_, app) = test_routes().await;
+
+        let response = get(&app, "/trades.xml", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/rss+xml; charset=utf-8"
+        );
+        let channel = rss::Channel::read_from(&body(response).await[..]).unwrap();
+        assert_eq!(channel.title, "NBA Trade News: Trade");
+        assert!(channel.last_build_date.is_some());
+        let titles: Vec<&str> = channel.items.iter().filter_map(|i| i.title()).collect();
+        assert_eq!(titles.len(), 2);
+        assert!(!titles.contains(&"Celtics sign forward"));
+        let item = channel
+            .items
+            .iter()
+            .find(|item| item.title() == Some("Lakers acquire guard"))
+            .unwrap();
+        assert_eq!(item.description(), Some("Details"));
+        let guid = item.guid().unwrap();
+        assert_eq!(guid.value, "urn:nba-trade-news:en:feed-1");
+        assert!(!guid.permalink);
+    }
+
+    #[tokio::test]
+    async fn test_japanese_atom_feed_uses_translations() {
+        let (_, app) = test_routes().await;
+
+        let response = get(&app, "/ja/team/lal.atom", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let feed = atom_syndication::Feed::read_from(&body(response).await[..]).unwrap();
+        assert_eq!(
+            feed.title.value,
+            "NBAトレードニュース: ロサンゼルス・レイカーズ"
+        );
+        assert_eq!(feed.lang.as_deref(), Some("ja"));
+        // 翻訳が終わっていない記事は載せない
+        assert_eq!(feed.entries.len(), 1);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title.value, "レイカーズがガードを獲得");
+        assert_eq!(entry.summary.as_ref().unwrap().value, "詳細");
+        assert_eq!(entry.id, "urn:nba-trade-news:ja:feed-1");
+        assert_eq!(entry.categories[0].term, "トレード");
+    }
+
+    #[tokio::test]
+    async fn test_conditional_get() {
+        let (store, app) = test_routes().await;
+
+        let response = get(&app, "/news.xml", &[]).await;
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+        let last_modified = response
+            .headers()
+            .get(header::LAST_MODIFIED)
+            .unwrap()
+            .clone();
+        assert!(last_modified.to_str().unwrap().ends_with(" GMT"));
+
+        let response = get(
+            &app,
+            "/news.xml",
+            &[("if-none-match", etag.to_str().unwrap())],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+        let since = [("if-modified-since", last_modified.to_str().unwrap())];
+        let response = get(&app, "/news.xml", &since).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // フィードごとにETagは異なる
+        let response = get(
+            &app,
+            "/news.atom",
+            &[("if-none-match", etag.to_str().unwrap())],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 新しい記事が保存されたら取得し直す
+        store
+            .save_news_items(vec![news_item("feed-4", "Warriors trade pick")])
+            .await
+            .unwrap();
+        let response = get(
+            &app,
+            "/news.xml",
+            &[("if-none-match", etag.to_str().unwrap())],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_feeds_are_not_found() {
+        let (_, app) = test_routes().await;
+
+        for uri in ["/unknown.xml", "/trades.json", "/team/xyz.xml"] {
+            let response = get(&app, uri, &[]).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+}
@@ -0,0 +1,631 @@
+//! ヘルスチェック
+//!
+//! ロードバランサーやオーケストレーター向けに、確認する範囲の異なる3つの
+//! エンドポイントを用意しています（認証・レート制限なし）。
+//!
+//! - `/health/live`: プロセスが応答するか（`/health`も同じ）。常に`200 OK`
+//! - `/health/ready`: データベースに接続でき、マイグレーションが適用済みか。
+//!   そうでなければ`503 Service Unavailable`
+//! - `/health/deep`: データベースに加えて、最後にスクレイピングできてからの時間、
+//!   未翻訳の記事の数、フィードごとの取得状態、接続プールの使用率
+//!
+//! 各項目は`healthy`・`degraded`・`unhealthy`のいずれかで、全体の状態は最も悪い
+//! 項目に合わせます。`degraded`・`unhealthy`にするしきい値は[`HealthConfig`]
+//! （環境変数）で変えられます。`/health/deep`は`unhealthy`のときだけ`503`を返します。
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::{FeedStatus, NewsFilter, SharedNewsRepository};
+use crate::scraper::models::RSS_FEEDS;
+use crate::utils::env_utils::env_parse;
+
+/// レスポンスに入れるサービス名
+const SERVICE_NAME: &str = "nba-trade-scraper";
+
+/// ヘルスチェックのしきい値
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthConfig {
+    /// データベースへの問い合わせを待つ時間
+    pub timeout: Duration,
+    /// 最後にスクレイピングできてからこの時間（分）が過ぎたら`degraded`
+    pub scrape_degraded_minutes: i64,
+    /// 最後にスクレイピングできてからこの時間（分）が過ぎたら`unhealthy`
+    pub scrape_unhealthy_minutes: i64,
+    /// 未翻訳の記事がこの数以上なら`degraded`
+    pub backlog_degraded: i64,
+    /// 未翻訳の記事がこの数以上なら`unhealthy`
+    pub backlog_unhealthy: i64,
+    /// この回数以上続けて取得に失敗したフィードは`degraded`
+    pub feed_failures_degraded: i32,
+    /// 接続プールの使用率（0.0〜1.0）がこれ以上なら`degraded`
+    pub pool_degraded_utilization: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(2),
+            scrape_degraded_minutes: 30,
+            scrape_unhealthy_minutes: 120,
+            backlog_degraded: 100,
+            backlog_unhealthy: 1000,
+            feed_failures_degraded: 3,
+            pool_degraded_utilization: 0.9,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// 環境変数から設定を読み込む（未設定の項目はデフォルト値）
+    ///
+    /// - `HEALTH_CHECK_TIMEOUT_MS`: データベースへの問い合わせを待つ時間（ミリ秒）
+    /// - `HEALTH_SCRAPE_DEGRADED_MINUTES`・`HEALTH_SCRAPE_UNHEALTHY_MINUTES`:
+    ///   最後にスクレイピングできてからの時間（分）
+    /// - `HEALTH_BACKLOG_DEGRADED`・`HEALTH_BACKLOG_UNHEALTHY`: 未翻訳の記事の数
+    /// - `HEALTH_FEED_FAILURES_DEGRADED`: フィードが続けて取得に失敗した回数
+    /// - `HEALTH_POOL_DEGRADED_PERCENT`: 接続プールの使用率（%）
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            timeout: env_parse("HEALTH_CHECK_TIMEOUT_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.timeout),
+            scrape_degraded_minutes: env_parse("HEALTH_SCRAPE_DEGRADED_MINUTES")
+                .unwrap_or(defaults.scrape_degraded_minutes),
+            scrape_unhealthy_minutes: env_parse("HEALTH_SCRAPE_UNHEALTHY_MINUTES")
+                .unwrap_or(defaults.scrape_unhealthy_minutes),
+            backlog_degraded: env_parse("HEALTH_BACKLOG_DEGRADED")
+                .unwrap_or(defaults.backlog_degraded),
+            backlog_unhealthy: env_parse("HEALTH_BACKLOG_UNHEALTHY")
+                .unwrap_or(defaults.backlog_unhealthy),
+            feed_failures_degraded: env_parse("HEALTH_FEED_FAILURES_DEGRADED")
+                .unwrap_or(defaults.feed_failures_degraded),
+            pool_degraded_utilization: env_parse::<f64>("HEALTH_POOL_DEGRADED_PERCENT")
+                .map(|percent| percent / 100.0)
+                .unwrap_or(defaults.pool_degraded_utilization),
+        }
+    }
+}
+
+/// 準備状態・詳細なヘルスチェックのルーター（`/health/live`は[`crate::create_app`]）
+pub fn routes(repository: SharedNewsRepository, config: HealthConfig) -> Router {
+    Router::new()
+        .route("/health/ready", get(ready))
+        .route("/health/deep", get(deep))
+        .with_state(HealthState { repository, config })
+}
+
+#[derive(Clone)]
+struct HealthState {
+    repository: SharedNewsRepository,
+    config: HealthConfig,
+}
+
+/// 項目ごと・全体の状態（悪い順に大きい）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    /// 動いているが、注意が必要
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    fn http_status(self) -> StatusCode {
+        match self {
+            HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::OK,
+        }
+    }
+}
+
+/// データベースの接続とマイグレーション
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseCheck {
+    pub status: HealthStatus,
+    /// 問い合わせにかかった時間（ミリ秒）
+    pub latency_ms: Option<u64>,
+    /// 適用されていないマイグレーションの数
+    pub pending_migrations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 最後にスクレイピングできた日時（いずれかのフィードを取得できた最後の日時）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrapeCheck {
+    pub status: HealthStatus,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub seconds_since_last_success: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 未翻訳の記事の数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklogCheck {
+    pub status: HealthStatus,
+    pub pending: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// フィードごとの取得状態
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedCheck {
+    pub source: String,
+    pub url: String,
+    pub status: HealthStatus,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub consecutive_failures: i32,
+    pub last_item_count: i32,
+    pub last_error: Option<String>,
+    pub last_error_code: Option<&'static str>,
+}
+
+/// 接続プールの使用率
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolCheck {
+    pub status: HealthStatus,
+    pub size: u32,
+    pub idle: u32,
+    pub max_size: u32,
+    /// 使用中の接続の割合（0.0〜1.0）
+    pub utilization: f64,
+}
+
+/// `/health/ready`のレスポンス
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    pub status: HealthStatus,
+    pub service: &'static str,
+    pub timestamp: DateTime<Utc>,
+    pub database: DatabaseCheck,
+}
+
+/// `/health/deep`のレスポンス
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepHealthReport {
+    pub status: HealthStatus,
+    pub service: &'static str,
+    pub timestamp: DateTime<Utc>,
+    pub database: DatabaseCheck,
+    pub scrape: ScrapeCheck,
+    pub translation_backlog: BacklogCheck,
+    pub feeds: Vec<FeedCheck>,
+    /// データベースを使わないとき（`NEWS_STORE=memory`）は`null`
+    pub pool: Option<PoolCheck>,
+}
+
+/// データベースに接続でき、マイグレーションが適用済みか確かめる
+pub async fn check_database(
+    repository: &SharedNewsRepository,
+    config: &HealthConfig,
+) -> DatabaseCheck {
+    let started = Instant::now();
+    let result = tokio::time::timeout(config.timeout, repository.pending_migrations()).await;
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    match result {
+        Ok(Ok(0)) => DatabaseCheck {
+            status: HealthStatus::Healthy,
+            latency_ms,
+            pending_migrations: Some(0),
+            message: None,
+        },
+        Ok(Ok(pending)) => DatabaseCheck {
+            status: HealthStatus::Unhealthy,
+            latency_ms,
+            pending_migrations: Some(pending),
+            message: Some(format!("{} migrations have not been applied", pending)),
+        },
+        Ok(Err(e)) => DatabaseCheck {
+            status: HealthStatus::Unhealthy,
+            latency_ms,
+            pending_migrations: None,
+            message: Some(e.to_string()),
+        },
+        Err(_) => DatabaseCheck {
+            status: HealthStatus::Unhealthy,
+            latency_ms: None,
+            pending_migrations: None,
+            message: Some(format!(
+                "Database did not respond within {} ms",
+                config.timeout.as_millis()
+            )),
+        },
+    }
+}
+
+/// 準備状態を確かめる
+pub async fn readiness(
+    repository: &SharedNewsRepository,
+    config: &HealthConfig,
+    now: DateTime<Utc>,
+) -> ReadinessReport {
+    let database = check_database(repository, config).await;
+    ReadinessReport {
+        status: database.status,
+        service: SERVICE_NAME,
+        timestamp: now,
+        database,
+    }
+}
+
+/// すべての項目を確かめる
+pub async fn deep_health(
+    repository: &SharedNewsRepository,
+    config: &HealthConfig,
+    now: DateTime<Utc>,
+) -> DeepHealthReport {
+    let backlog_filter = NewsFilter {
+        translation_status: Some("pending".to_string()),
+        ..NewsFilter::default()
+    };
+    let (database, feed_statuses, backlog) = tokio::join!(
+        check_database(repository, config),
+        tokio::time::timeout(config.timeout, repository.feed_statuses()),
+        tokio::time::timeout(
+            config.timeout,
+            repository.count_matching_news(&backlog_filter)
+        ),
+    );
+    let feed_statuses = flatten(feed_statuses, config.timeout);
+
+    let scrape = check_scrape(
+        feed_statuses.as_deref().map_err(String::as_str),
+        config,
+        now,
+    );
+    let translation_backlog = check_backlog(flatten(backlog, config.timeout), config);
+    let feeds = check_feeds(feed_statuses.as_deref().unwrap_or_default(), config);
+    let pool = repository.pool_status().map(|pool| {
+        let utilization = pool.utilization();
+        PoolCheck {
+            status: if utilization >= config.pool_degraded_utilization {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Healthy
+            },
+            size: pool.size,
+            idle: pool.idle,
+            max_size: pool.max_size,
+            utilization,
+        }
+    });
+
+    let status = [database.status, scrape.status, translation_backlog.status]
+        .into_iter()
+        .chain(feeds.iter().map(|feed| feed.status))
+        .chain(pool.as_ref().map(|pool| pool.status))
+        .max()
+        .unwrap_or(HealthStatus::Healthy);
+
+    DeepHealthReport {
+        status,
+        service: SERVICE_NAME,
+        timestamp: now,
+        database,
+        scrape,
+        translation_backlog,
+        feeds,
+        pool,
+    }
+}
+
+/// タイムアウトをエラーのメッセージにまとめる
+fn flatten<T>(
+    result: Result<anyhow::Result<T>, tokio::time::error::Elapsed>,
+    timeout: Duration,
+) -> Result<T, String> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!(
+            "Database did not respond within {} ms",
+            timeout.as_millis()
+        )),
+    }
+}
+
+fn check_scrape(
+    feed_statuses: Result<&[FeedStatus], &str>,
+    config: &HealthConfig,
+    now: DateTime<Utc>,
+) -> ScrapeCheck {
+    let feed_statuses = match feed_statuses {
+        Ok(statuses) => statuses,
+        Err(message) => {
+            return ScrapeCheck {
+                status: HealthStatus::Unhealthy,
+                last_success_at: None,
+                seconds_since_last_success: None,
+                message: Some(message.to_string()),
+            }
+        }
+    };
+
+    let Some(last_success_at) = feed_statuses
+        .iter()
+        .filter_map(|status| status.last_success_at)
+        .max()
+    else {
+        return ScrapeCheck {
+            status: HealthStatus::Degraded,
+            last_success_at: None,
+            seconds_since_last_success: None,
+            message: Some("No successful scrape has been recorded".to_string()),
+        };
+    };
+
+    let elapsed = now - last_success_at;
+    let status = if elapsed >= chrono::Duration::minutes(config.scrape_unhealthy_minutes) {
+        HealthStatus::Unhealthy
+    } else if elapsed >= chrono::Duration::minutes(config.scrape_degraded_minutes) {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    };
+    ScrapeCheck {
+        status,
+        last_success_at: Some(last_success_at),
+        seconds_since_last_success: Some(elapsed.num_seconds().max(0)),
+        message: None,
+    }
+}
+
+fn check_backlog(pending: Result<i64, String>, config: &HealthConfig) -> BacklogCheck {
+    match pending {
+        Ok(pending) => BacklogCheck {
+            status: if pending >= config.backlog_unhealthy {
+                HealthStatus::Unhealthy
+            } else if pending >= config.backlog_degraded {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Healthy
+            },
+            pending: Some(pending),
+            message: None,
+        },
+        Err(message) => BacklogCheck {
+            status: HealthStatus::Unhealthy,
+            pending: None,
+            message: Some(message),
+        },
+    }
+}
+
+/// 設定されているフィードごとの状態（一度も取得していないフィードは`degraded`）
+fn check_feeds(feed_statuses: &[FeedStatus], config: &HealthConfig) -> Vec<FeedCheck> {
+    RSS_FEEDS
+        .iter()
+        .map(|(url, source)| {
+            let source = source.to_string();
+            match feed_statuses.iter().find(|status| status.source == source) {
+                Some(status) => FeedCheck {
+                    status: if status.consecutive_failures >= config.feed_failures_degraded
+                        || status.last_success_at.is_none()
+                    {
+                        HealthStatus::Degraded
+                    } else {
+                        HealthStatus::Healthy
+                    },
+                    source,
+                    url: status.url.clone(),
+                    last_attempt_at: Some(status.last_attempt_at),
+                    last_success_at: status.last_success_at,
+                    consecutive_failures: status.consecutive_failures,
+                    last_item_count: status.last_item_count,
+                    last_error: status.last_error.clone(),
+                    last_error_code: status.last_error_code.map(|code| code.as_str()),
+                },
+                None => FeedCheck {
+                    source,
+                    url: url.to_string(),
+                    status: HealthStatus::Degraded,
+                    last_attempt_at: None,
+                    last_success_at: None,
+                    consecutive_failures: 0,
+                    last_item_count: 0,
+                    last_error: None,
+                    last_error_code: None,
+                },
+            }
+        })
+        .collect()
+}
+
+async fn ready(State(state): State<HealthState>) -> Response {
+    let report = readiness(&state.repository, &state.config, Utc::now()).await;
+    (report.status.http_status(), Json(report)).into_response()
+}
+
+async fn deep(State(state): State<HealthState>) -> Response {
+    let report = deep_health(&state.repository, &state.config, Utc::now()).await;
+    (report.status.http_status(), Json(report)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::news_item;
+    use crate::db::{FeedFetch, InMemoryNewsStore, NewsRepository};
+    use crate::errors::ErrorCode;
+    use crate::scraper::{NewsItem, NewsSource};
+    use std::sync::Arc;
+
+    fn fetch(source: NewsSource, at: DateTime<Utc>, error: Option<ErrorCode>) -> FeedFetch {
+        let url = RSS_FEEDS
+            .iter()
+            .find(|(_, feed_source)| *feed_source == source)
+            .map(|(url, _)| url.to_string())
+            .unwrap();
+        FeedFetch {
+            source: source.to_string(),
+            url,
+            fetched_at: at,
+            item_count: if error.is_some() { 0 } else { 10 },
+            error: error.map(|code| (code, "connection refused".to_string())),
+        }
+    }
+
+    async fn scraped_store(at: DateTime<Utc>) -> Arc<InMemoryNewsStore> {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .record_feed_fetch(&fetch(NewsSource::ESPN, at, None))
+            .await
+            .unwrap();
+        store
+            .record_feed_fetch(&fetch(NewsSource::RealGM, at, None))
+            .await
+            .unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_is_healthy_after_recent_scrape() {
+        let now = Utc::now();
+        let repository: SharedNewsRepository =
+            scraped_store(now - chrono::Duration::minutes(5)).await;
+
+        let report = deep_health(&repository, &HealthConfig::default(), now).await;
+        assert_eq!(report.status, HealthStatus::Healthy);
+        assert_eq!(report.database.pending_migrations, Some(0));
+        assert_eq!(report.scrape.seconds_since_last_success, Some(300));
+        assert_eq!(report.translation_backlog.pending, Some(0));
+        assert_eq!(report.feeds.len(), RSS_FEEDS.len());
+        assert!(report.pool.is_none());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "healthy");
+        assert_eq!(json["feeds"][0]["source"], "ESPN");
+        assert_eq!(json["feeds"][0]["lastItemCount"], 10);
+        assert!(json["translationBacklog"]["pending"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_without_scrape_is_degraded() {
+        let repository: SharedNewsRepository = Arc::new(InMemoryNewsStore::new());
+
+        let report = deep_health(&repository, &HealthConfig::default(), Utc::now()).await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.scrape.status, HealthStatus::Degraded);
+        assert!(report
+            .feeds
+            .iter()
+            .all(|feed| feed.status == HealthStatus::Degraded && feed.last_attempt_at.is_none()));
+        // データベースの準備はできている
+        let readiness = readiness(&repository, &HealthConfig::default(), Utc::now()).await;
+        assert_eq!(readiness.status, HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_thresholds_flip_status() {
+        let now = Utc::now();
+        let store = scraped_store(now - chrono::Duration::minutes(45)).await;
+        let config = HealthConfig::default();
+
+        // 30分以上スクレイピングできていなければdegraded、120分以上ならunhealthy
+        let repository: SharedNewsRepository = store.clone();
+        let report = deep_health(&repository, &config, now).await;
+        assert_eq!(report.scrape.status, HealthStatus::Degraded);
+        assert_eq!(report.status, HealthStatus::Degraded);
+        let report = deep_health(&repository, &config, now + chrono::Duration::hours(2)).await;
+        assert_eq!(report.scrape.status, HealthStatus::Unhealthy);
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+
+        // 続けて取得に失敗したフィードはdegraded
+        for _ in 0..config.feed_failures_degraded {
+            store
+                .record_feed_fetch(&fetch(
+                    NewsSource::RealGM,
+                    now,
+                    Some(ErrorCode::FeedFetchFailed),
+                ))
+                .await
+                .unwrap();
+        }
+        let report = deep_health(&repository, &config, now).await;
+        let realgm = report
+            .feeds
+            .iter()
+            .find(|feed| feed.source == "RealGM")
+            .unwrap();
+        assert_eq!(realgm.status, HealthStatus::Degraded);
+        assert_eq!(realgm.consecutive_failures, config.feed_failures_degraded);
+        assert_eq!(realgm.last_error_code, Some("FEED_FETCH_FAILED"));
+
+        // 未翻訳の記事の数
+        store
+            .save_news_items(
+                (0..3)
+                    .map(|i| NewsItem {
+                        published_at: now,
+                        ..news_item(
+                            &format!("health-{}", i),
+                            &format!("Lakers trade rumor {}", i),
+                        )
+                    })
+                    .collect(),
+            )
+            .await
+            .unwrap();
+        let config = HealthConfig {
+            scrape_degraded_minutes: 60,
+            feed_failures_degraded: 10,
+            backlog_degraded: 2,
+            backlog_unhealthy: 3,
+            ..HealthConfig::default()
+        };
+        let report = deep_health(&repository, &config, now).await;
+        assert_eq!(report.translation_backlog.pending, Some(3));
+        assert_eq!(report.translation_backlog.status, HealthStatus::Unhealthy);
+        assert_eq!(report.status.http_status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_return_status_codes() {
+        use tower::util::ServiceExt;
+
+        let repository: SharedNewsRepository = scraped_store(Utc::now()).await;
+        let app = routes(repository, HealthConfig::default());
+        for uri in ["/health/ready", "/health/deep"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .uri(uri)
+                        .body(axum::body::Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["status"], "healthy");
+            assert_eq!(json["database"]["status"], "healthy");
+        }
+    }
+}
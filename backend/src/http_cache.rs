@@ -0,0 +1,323 @@
+//! GETで受け取ったGraphQLクエリの実行とHTTPキャッシュ
+//!
+//! 未認証のGETリクエストには、記事の内容の版（最後に保存・翻訳された日時と最後の上書き）と
+//! クエリ文字列から作った`ETag`と`Cache-Control`を付けます（`Accept-Language`で言語を
+//! 指定されたら言語もETagに含めます）。`If-None-Match`が一致すればクエリを実行せずに`304 Not Modified`を
+//! 返すため、ブラウザやCDNのキャッシュが使えます。
+//!
+//! 認証済みのリクエストやエラーを含む結果はキャッシュさせません。
+//! ミューテーションはGETでは実行できません（`405 Method Not Allowed`）。
+
+use std::time::Duration;
+
+use async_graphql_axum::GraphQLResponse;
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::auth::{self, Principal};
+use crate::db::{content_version, ContentVersion, SharedNewsRepository};
+use crate::graphql::{with_locale, with_principal, AppSchema};
+use crate::locale::Locale;
+use crate::rate_limit::{operation_kind_of, OperationKind};
+use crate::utils::env_utils::env_parse;
+
+/// キャッシュさせないレスポンスの`Cache-Control`
+const NO_STORE: &str = "no-store";
+
+/// 認証情報と言語によって結果が変わりうるため、キャッシュのキーに含めてもらう
+const VARY: &str = "Authorization, X-API-Key, Accept-Language";
+
+/// GETレスポンスのキャッシュ設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpCacheConfig {
+    /// `Cache-Control`の`max-age`
+    pub max_age: Duration,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(60),
+        }
+    }
+}
+
+impl HttpCacheConfig {
+    /// 環境変数`GRAPHQL_CACHE_MAX_AGE`（秒）から設定を読み込む
+    pub fn from_env() -> Self {
+        env_parse("GRAPHQL_CACHE_MAX_AGE").map_or_else(Self::default, |seconds| Self {
+            max_age: Duration::from_secs(seconds),
+        })
+    }
+
+    fn cache_control(&self) -> String {
+        format!("public, max-age={}", self.max_age.as_secs())
+    }
+}
+
+/// クエリ文字列（`?query=...&variables=...`）で受け取ったGraphQLリクエストを実行する
+pub async fn execute_get(
+    schema: &AppSchema,
+    principal: Option<Principal>,
+    raw_query: &str,
+    headers: &HeaderMap,
+) -> Response {
+    let request = match async_graphql::http::parse_query_string(raw_query) {
+        Ok(request) => request,
+        Err(e) => {
+            return auth::error_response(StatusCode::BAD_REQUEST, &e.to_string(), "BAD_REQUEST")
+        }
+    };
+
+    // 永続化クエリにはミューテーションを登録しないため、本文だけを確認すれば足りる
+    if !request.query.is_empty()
+        && operation_kind_of(&request.query, request.operation_name.as_deref())
+            == OperationKind::Mutation
+    {
+        let mut response = auth::error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Mutations must be sent with POST",
+            "METHOD_NOT_ALLOWED",
+        );
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static("POST"));
+        return response;
+    }
+
+    let locale = Locale::from_headers(headers);
+    let request = with_locale(request, locale);
+
+    // 管理用フィールドなど、認証済みの結果はキャッシュさせない
+    if principal.is_some() {
+        let response = schema.execute(with_principal(request, principal)).await;
+        return with_cache_headers(
+            GraphQLResponse::from(response).into_response(),
+            NO_STORE,
+            None,
+        );
+    }
+
+    let config = schema
+        .data::<HttpCacheConfig>()
+        .cloned()
+        .unwrap_or_default();
+    let etag = match schema.data::<SharedNewsRepository>() {
+        // 非表示・分類の上書きでも結果が変わるため、上書きも含めた版を使う
+        Some(repository) => match content_version(repository.as_ref()).await {
+            Ok(version) => Some(etag(&version, raw_query, locale)),
+            Err(e) => {
+                warn!("Failed to read content version: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some(etag) = &etag {
+        if headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| etag_matches(value, etag))
+        {
+            return with_cache_headers(
+                StatusCode::NOT_MODIFIED.into_response(),
+                &config.cache_control(),
+                Some(etag),
+            );
+        }
+    }
+
+    let response = schema.execute(request).await;
+    let cacheable = response.is_ok() && etag.is_some();
+    let response = GraphQLResponse::from(response).into_response();
+    if cacheable {
+        with_cache_headers(response, &config.cache_control(), etag.as_deref())
+    } else {
+        with_cache_headers(response, NO_STORE, None)
+    }
+}
+
+/// 記事の内容の版・キー（クエリ文字列など）・言語から強いETagを作る
+pub(crate) fn etag(version: &ContentVersion, key: &str, locale: Option<Locale>) -> String {
+    let mut hasher = Sha256::new();
+    if let Some(last_modified) = version.last_modified {
+        hasher.update(last_modified.to_rfc3339().as_bytes());
+    }
+    hasher.update(b"\n");
+    if let Some(last_override_id) = version.last_override_id {
+        hasher.update(last_override_id.to_string().as_bytes());
+    }
+    hasher.update(b"\n");
+    hasher.update(key.as_bytes());
+    if let Some(locale) = locale {
+        hasher.update(b"\n");
+        hasher.update(locale.as_str().as_bytes());
+    }
+    let digest = format!("{:x}", hasher.finalize());
+    format!("\"{}\"", &digest[..32])
+}
+
+/// `If-None-Match`（カンマ区切り、弱いETagや`*`も可）がETagに一致するか
+pub(crate) fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+fn with_cache_headers(mut response: Response, cache_control: &str, etag: Option<&str>) -> Response {
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(cache_control) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+    headers.insert(header::VARY, HeaderValue::from_static(VARY));
+    if let Some(value) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(header::ETAG, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+    use crate::db::test_support::news_item;
+    use crate::db::{InMemoryAuthStore, InMemoryNewsStore, NewsOverride, NewsRepository};
+    use crate::graphql::create_schema;
+    use chrono::Utc;
+    use std::sync::Arc;
+
+    fn if_none_match(etag: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(etag).unwrap());
+        headers
+    }
+
+    const QUERY: &str = "query=%7B%20tradeNews%20%7B%20id%20%7D%20%7D";
+
+    #[tokio::test]
+    async fn test_get_responses_are_revalidated_with_etag() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        let schema = create_schema(store.clone(), Arc::new(InMemoryAuthStore::new()));
+
+        let response = execute_get(&schema, None, QUERY, &HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, max-age=60"
+        );
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+        let response =
+            execute_get(&schema, None, QUERY, &if_none_match(etag.to_str().unwrap())).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+
+        // 新しい記事が保存されるとETagが変わる
+        store
+            .save_news_items(vec![news_item("etag-1", "Lakers acquire guard")])
+            .await
+            .unwrap();
+        let response =
+            execute_get(&schema, None, QUERY, &if_none_match(etag.to_str().unwrap())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(header::ETAG).unwrap(), &etag);
+    }
+
+    #[tokio::test]
+    async fn test_etag_changes_when_news_is_overridden() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![news_item("etag-override", "Lakers acquire guard")])
+            .await
+            .unwrap();
+        let schema = create_schema(store.clone(), Arc::new(InMemoryAuthStore::new()));
+
+        let response = execute_get(&schema, None, QUERY, &HeaderMap::new()).await;
+        let etag = response.headers().get(header::ETAG).unwrap().clone();
+
+        // 非表示にしても記事の保存日時は変わらないが、結果は変わる
+        store
+            .override_news(
+                "etag-override",
+                &NewsOverride {
+                    hidden: Some(true),
+                    ..NewsOverride::default()
+                },
+                "jwt:editor",
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let response =
+            execute_get(&schema, None, QUERY, &if_none_match(etag.to_str().unwrap())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers().get(header::ETAG).unwrap(), &etag);
+    }
+
+    #[tokio::test]
+    async fn test_get_rejects_mutations() {
+        let schema = create_schema(
+            Arc::new(InMemoryNewsStore::new()),
+            Arc::new(InMemoryAuthStore::new()),
+        );
+        let query = "query=mutation%20%7B%20scrapeRss%20%7B%20id%20%7D%20%7D";
+
+        let response = execute_get(&schema, None, query, &HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers().get(header::ALLOW).unwrap(), "POST");
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_and_failed_responses_are_not_cached() {
+        let schema = create_schema(
+            Arc::new(InMemoryNewsStore::new()),
+            Arc::new(InMemoryAuthStore::new()),
+        );
+
+        let principal = Principal::api_key("frontend", Role::Reader);
+        let response = execute_get(&schema, Some(principal), QUERY, &HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            NO_STORE
+        );
+        assert!(!response.headers().contains_key(header::ETAG));
+
+        let invalid = "query=%7B%20invalidField%20%7D";
+        let response = execute_get(&schema, None, invalid, &HeaderMap::new()).await;
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            NO_STORE
+        );
+        assert!(!response.headers().contains_key(header::ETAG));
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let version = ContentVersion::default();
+        let etag = etag(&version, QUERY, None);
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&format!("\"other\", W/{etag}"), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+        let saved = ContentVersion {
+            last_modified: Some(Utc::now()),
+            ..version
+        };
+        assert_ne!(etag, super::etag(&saved, QUERY, None));
+        let overridden = ContentVersion {
+            last_override_id: Some(1),
+            ..version
+        };
+        assert_ne!(etag, super::etag(&overridden, QUERY, None));
+        // 言語によって結果が変わるため、ETagも変わる
+        assert_ne!(etag, super::etag(&version, QUERY, Some(Locale::Ja)));
+    }
+}
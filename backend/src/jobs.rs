@@ -0,0 +1,464 @@
+//! スクレイピング・翻訳のバックグラウンドジョブ
+//!
+//! `scrapeRss`・`translatePendingNews`はHTTPリクエストの中で処理せず、
+//! [`JobRunner::enqueue`]でジョブを登録してすぐにIDを返します。処理は
+//! バックグラウンドのタスクで行い、フィード・記事を1つ処理するたびに進捗を
+//! リポジトリに保存します。クライアントは`job(id)`クエリでポーリングするか、
+//! `jobProgress(id)`サブスクリプションで進捗を受け取ります。
+//!
+//! 同じ種類のジョブが実行待ち・実行中なら新しく登録せず、そのジョブを返します
+//! （ボタンの連打などで同じ処理が重ならないように）。
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+use tracing::{error, info, warn};
+
+use crate::db::models::TradeNews;
+use crate::db::{
+    record_feed_fetch, save_scraped_news, FeedFetch, Job, JobKind, SharedNewsRepository,
+};
+use crate::errors::{error_code, AppError, ErrorCode, ErrorStage, ItemError};
+use crate::metrics::metrics;
+use crate::scraper::models::{RssFeed, RSS_FEEDS};
+use crate::scraper::RssParser;
+use crate::services::{translate_with_metrics, AmazonTranslateService, TranslationService};
+use crate::shutdown::BackgroundTasks;
+
+/// 1回の翻訳ジョブで翻訳する記事の最大数
+pub const TRANSLATION_BATCH_SIZE: i64 = 10;
+
+/// 翻訳のレート制限を考慮して、記事ごとに空ける間隔
+const TRANSLATION_INTERVAL: Duration = Duration::from_millis(500);
+
+/// この時間進捗が更新されていない実行中のジョブは、止まったものとみなす
+pub const STALE_JOB_MINUTES: i64 = 10;
+
+/// `jobProgress`サブスクリプションがジョブの状態を確認する間隔
+pub const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// ジョブの登録結果
+enum Registration {
+    /// 登録したジョブ
+    New(Job),
+    /// 実行待ち・実行中だった同じ種類のジョブ
+    Active(Job),
+}
+
+/// ジョブを登録してバックグラウンドで実行する
+#[derive(Clone)]
+pub struct JobRunner {
+    repository: SharedNewsRepository,
+    /// 翻訳サービス（`None`ならジョブごとにAmazon Translateのクライアントを作る）
+    translator: Option<Arc<dyn TranslationService>>,
+    translation_interval: Duration,
+    /// ジョブを実行するタスク（終了時に実行中のジョブを待つ）
+    tasks: BackgroundTasks,
+}
+
+impl JobRunner {
+    pub fn new(repository: SharedNewsRepository) -> Self {
+        Self {
+            repository,
+            translator: None,
+            translation_interval: TRANSLATION_INTERVAL,
+            tasks: BackgroundTasks::default(),
+        }
+    }
+
+    /// ジョブを実行するタスクを、終了時に待つタスクの集まりに入れる
+    pub fn with_tasks(mut self, tasks: BackgroundTasks) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// 翻訳サービスと記事ごとの間隔を差し替える（テスト用）
+    pub fn with_translator(
+        mut self,
+        translator: Arc<dyn TranslationService>,
+        interval: Duration,
+    ) -> Self {
+        self.translator = Some(translator);
+        self.translation_interval = interval;
+        self
+    }
+
+    /// ジョブを実行するタスクの集まり
+    pub fn tasks(&self) -> &BackgroundTasks {
+        &self.tasks
+    }
+
+    pub fn repository(&self) -> &SharedNewsRepository {
+        &self.repository
+    }
+
+    /// ジョブを登録して実行を始める
+    ///
+    /// 同じ種類のジョブが実行待ち・実行中なら、新しく登録せずにそのジョブを返します。
+    pub async fn enqueue(&self, kind: JobKind, requested_by: &str) -> Result<Job> {
+        let job = match self.register(kind, requested_by).await? {
+            Registration::New(job) => job,
+            Registration::Active(active) => return Ok(active),
+        };
+
+        let runner = self.clone();
+        let queued = job.clone();
+        self.tasks.spawn(async move { runner.run(queued).await });
+
+        Ok(job)
+    }
+
+    /// ジョブを登録して、終わるまで待つ（スケジューラー用）
+    ///
+    /// 同じ種類のジョブが実行待ち・実行中なら、新しく登録せず待たずにそのジョブを返します。
+    pub async fn run_now(&self, kind: JobKind, requested_by: &str) -> Result<Job> {
+        match self.register(kind, requested_by).await? {
+            Registration::New(job) => Ok(self.run(job).await),
+            Registration::Active(active) => Ok(active),
+        }
+    }
+
+    /// ジョブを登録する（同じ種類のジョブが実行待ち・実行中なら登録しない）
+    async fn register(&self, kind: JobKind, requested_by: &str) -> Result<Registration> {
+        let updated_since = Utc::now() - chrono::Duration::minutes(STALE_JOB_MINUTES);
+        let job = Job::new(kind, requested_by);
+        if let Some(active) = self.repository.create_job(&job, updated_since).await? {
+            info!("{} job {} is already in progress", kind.as_str(), active.id);
+            return Ok(Registration::Active(active));
+        }
+        info!("Enqueued {} job {}", kind.as_str(), job.id);
+        Ok(Registration::New(job))
+    }
+
+    async fn run(&self, mut job: Job) -> Job {
+        job.start();
+        self.save(&job).await;
+
+        let started = Instant::now();
+        let result = match job.kind {
+            JobKind::Scrape => self.scrape(&mut job).await,
+            JobKind::Translate => self.translate(&mut job).await,
+        };
+        metrics().record_job(job.kind.as_str(), started.elapsed(), result.is_ok());
+        if let Err(e) = &result {
+            error!("{} job {} failed: {}", job.kind.as_str(), job.id, e);
+        }
+        job.finish(result.err().map(|e| (error_code(&e), e.to_string())));
+        self.save(&job).await;
+
+        info!(
+            "{} job {} finished: {} saved, {} updated, {} translated, {} errors",
+            job.kind.as_str(),
+            job.id,
+            job.saved_count,
+            job.updated_count,
+            job.translated_count,
+            job.error_count()
+        );
+        job
+    }
+
+    /// 進捗を保存する（保存できなくてもジョブは続ける）
+    async fn save(&self, job: &Job) {
+        if let Err(e) = self.repository.save_job(job).await {
+            warn!("Failed to save progress of job {}: {}", job.id, e);
+        }
+    }
+
+    /// RSSフィードを1つずつ取得し、最後にまとめて保存する
+    ///
+    /// フィードごとの取得結果はヘルスチェックのために記録します。
+    async fn scrape(&self, job: &mut Job) -> Result<()> {
+        let parser = RssParser::new();
+        job.total = Some(RSS_FEEDS.len() as i32);
+        self.save(job).await;
+
+        let mut news_items = Vec::new();
+        for (url, source) in RSS_FEEDS {
+            let result = parser.fetch_feed(&RssFeed::new(url, source.clone())).await;
+            let fetch = FeedFetch::new(source.to_string(), *url, &result);
+            record_feed_fetch(self.repository.as_ref(), fetch).await;
+            match result {
+                Ok(mut news) => {
+                    info!("Fetched {} items from {}", news.len(), source);
+                    news_items.append(&mut news);
+                }
+                Err(e) => {
+                    error!("Failed to fetch feed from {}: {}", source, e);
+                    let code = error_code(&e);
+                    let stage = if code == ErrorCode::FeedParseFailed {
+                        ErrorStage::Parse
+                    } else {
+                        ErrorStage::Fetch
+                    };
+                    job.errors
+                        .push(ItemError::new(source.to_string(), stage, code, e));
+                }
+            }
+            job.advance();
+            self.save(job).await;
+        }
+
+        news_items.sort_by_key(|item| std::cmp::Reverse(item.published_at));
+        let result = save_scraped_news(self.repository.as_ref(), news_items).await?;
+
+        job.saved_count = result.saved_count as i32;
+        job.updated_count = result.updated_count as i32;
+        job.skipped_count = result.skipped_count as i32;
+        job.errors.extend(result.errors);
+        Ok(())
+    }
+
+    /// 未翻訳の記事を1件ずつ翻訳する
+    async fn translate(&self, job: &mut Job) -> Result<()> {
+        let translator = match &self.translator {
+            Some(translator) => translator.clone(),
+            None => Arc::new(AmazonTranslateService::new().await),
+        };
+
+        let pending_items = self
+            .repository
+            .get_pending_translations(TRANSLATION_BATCH_SIZE)
+            .await?;
+        info!("Found {} items to translate", pending_items.len());
+        job.total = Some(pending_items.len() as i32);
+        self.save(job).await;
+
+        for (index, item) in pending_items.iter().enumerate() {
+            // レート制限を考慮して少し待機
+            if index > 0 {
+                tokio::time::sleep(self.translation_interval).await;
+            }
+
+            match self.translate_item(translator.as_ref(), item).await {
+                Ok(errors) => {
+                    job.translated_count += 1;
+                    job.errors.extend(errors);
+                }
+                Err(e) => job.errors.push(e),
+            }
+            job.advance();
+            self.save(job).await;
+        }
+
+        Ok(())
+    }
+
+    /// 記事のタイトルと説明文を翻訳して保存する
+    ///
+    /// 保存できた場合は説明文の翻訳エラーを、保存できなかった場合はその理由を返します。
+    async fn translate_item(
+        &self,
+        translator: &dyn TranslationService,
+        item: &TradeNews,
+    ) -> std::result::Result<Vec<ItemError>, ItemError> {
+        let title_ja = translate_with_metrics(translator, &item.title, "en", "ja")
+            .await
+            .map_err(|e| {
+                error!("Failed to translate title for {}: {}", item.id, e);
+                let e = AppError::from(e);
+                ItemError::new(&item.id, ErrorStage::TranslateTitle, e.code(), e)
+            })?;
+
+        // 説明文の翻訳に失敗してもタイトルだけは保存する
+        let mut errors = Vec::new();
+        let description_ja = match &item.description {
+            Some(desc) => match translate_with_metrics(translator, desc, "en", "ja").await {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    error!("Failed to translate description for {}: {}", item.id, e);
+                    let e = AppError::from(e);
+                    errors.push(ItemError::new(
+                        &item.id,
+                        ErrorStage::TranslateDescription,
+                        e.code(),
+                        e,
+                    ));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        self.repository
+            .save_translation(&item.id, &title_ja, description_ja.as_deref())
+            .await
+            .map_err(|e| {
+                error!("Failed to update database for {}: {}", item.id, e);
+                ItemError::new(&item.id, ErrorStage::Save, error_code(&e), e)
+            })?;
+
+        info!("Successfully translated item: {}", item.id);
+        Ok(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::news_item;
+    use crate::db::{InMemoryNewsStore, JobStatus, NewsRepository};
+    use crate::services::TranslationError;
+    use async_trait::async_trait;
+
+    /// 「fail」を含む文は翻訳に失敗し、「busy」を含む文はレート制限に達する翻訳サービス
+    struct FakeTranslator;
+
+    #[async_trait]
+    impl TranslationService for FakeTranslator {
+        async fn translate(
+            &self,
+            text: &str,
+            _source_lang: &str,
+            _target_lang: &str,
+        ) -> std::result::Result<String, TranslationError> {
+            if text.contains("busy") {
+                return Err(TranslationError::RateLimitExceeded);
+            }
+            if text.contains("fail") {
+                return Err(TranslationError::TranslationFailed(text.to_string()));
+            }
+            Ok(format!("訳: {text}"))
+        }
+    }
+
+    async fn wait_until_finished(repository: &dyn NewsRepository, id: &str) -> Job {
+        for _ in 0..100 {
+            let job = repository.get_job(id).await.unwrap().unwrap();
+            if job.status.is_finished() {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Job {id} did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_translate_job_reports_progress_and_errors() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![
+                news_item("job-1", "Lakers acquire guard"),
+                news_item("job-2", "Trade talks fail"),
+                news_item("job-3", "Front office is busy"),
+            ])
+            .await
+            .unwrap();
+        let runner =
+            JobRunner::new(store.clone()).with_translator(Arc::new(FakeTranslator), Duration::ZERO);
+
+        let job = runner
+            .enqueue(JobKind::Translate, "api_key:test")
+            .await
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.requested_by, "api_key:test");
+
+        let job = wait_until_finished(store.as_ref(), &job.id).await;
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.total, Some(3));
+        assert_eq!(job.processed, 3);
+        assert_eq!(job.translated_count, 1);
+        let errors: Vec<_> = job
+            .errors
+            .iter()
+            .map(|e| (e.item_id.as_str(), e.stage, e.code))
+            .collect();
+        assert!(errors.contains(&(
+            "job-2",
+            ErrorStage::TranslateTitle,
+            ErrorCode::TranslationFailed
+        )));
+        assert!(errors.contains(&(
+            "job-3",
+            ErrorStage::TranslateTitle,
+            ErrorCode::TranslationRateLimited
+        )));
+
+        let news = store.get_news("job-1").await.unwrap().unwrap();
+        assert_eq!(news.title_ja.as_deref(), Some("訳: Lakers acquire guard"));
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_enqueued_jobs() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![news_item("job-1", "Lakers acquire guard")])
+            .await
+            .unwrap();
+        let tasks = BackgroundTasks::new();
+        let runner = JobRunner::new(store.clone())
+            .with_translator(Arc::new(FakeTranslator), Duration::from_millis(50))
+            .with_tasks(tasks.clone());
+
+        let job = runner
+            .enqueue(JobKind::Translate, "api_key:test")
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        // 終了時は実行中のジョブが終わるまで待つ
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        assert!(tasks.drain(deadline).await);
+        let job = store.get_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.translated_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_now_waits_for_the_job() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![news_item("job-1", "Lakers acquire guard")])
+            .await
+            .unwrap();
+        let runner =
+            JobRunner::new(store.clone()).with_translator(Arc::new(FakeTranslator), Duration::ZERO);
+
+        // 終わったジョブが返り、ジョブの状態にも記録される
+        let job = runner
+            .run_now(JobKind::Translate, "scheduler")
+            .await
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.requested_by, "scheduler");
+        assert_eq!(job.translated_count, 1);
+        assert_eq!(store.get_job(&job.id).await.unwrap(), Some(job));
+
+        // 手動のジョブが実行中なら、実行せずにそのジョブを返す
+        let mut running = Job::new(JobKind::Translate, "jwt:alice");
+        running.start();
+        store.save_job(&running).await.unwrap();
+        let job = runner
+            .run_now(JobKind::Translate, "scheduler")
+            .await
+            .unwrap();
+        assert_eq!(job.id, running.id);
+        assert_eq!(job.status, JobStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_active_job_is_reused() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        let runner = JobRunner::new(store.clone());
+
+        // 実行中のジョブがあれば、新しく登録せずにそれを返す
+        let mut running = Job::new(JobKind::Translate, "jwt:alice");
+        running.start();
+        store.save_job(&running).await.unwrap();
+        let job = runner.enqueue(JobKind::Translate, "jwt:bob").await.unwrap();
+        assert_eq!(job.id, running.id);
+
+        // 止まったジョブは失敗にして、新しく登録する
+        running.updated_at = Utc::now() - chrono::Duration::minutes(STALE_JOB_MINUTES + 1);
+        store.save_job(&running).await.unwrap();
+        let job = runner.enqueue(JobKind::Translate, "jwt:bob").await.unwrap();
+        assert_ne!(job.id, running.id);
+        assert_eq!(job.requested_by, "jwt:bob");
+        let abandoned = store.get_job(&running.id).await.unwrap().unwrap();
+        assert_eq!(abandoned.status, JobStatus::Failed);
+        assert_eq!(abandoned.failure_code, Some(ErrorCode::Internal));
+    }
+}
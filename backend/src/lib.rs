@@ -6,20 +6,34 @@
 //!
 //! - **RSSフィード解析**: ESPNやRealGMなどの主要ソースからニュースを自動収集
 //! - **GraphQL API**: フレキシブルなクエリインターフェース
+//! - **REST API**: GraphQLを使わないクライアント向けの読み取り専用API（OpenAPI付き）
 //! - **データベース**: トレード情報の永続化と管理
 //!
 //! ## モジュール構成
 //!
+//! - [`auth`] - APIキー・JWTによる認証とロールによる認可
 //! - [`db`] - データベース接続とモデル定義
+//! - [`errors`] - エラーの種類とGraphQLのエラーコード
+//! - [`feeds`] - 記事のRSS・Atomフィード
 //! - [`graphql`] - GraphQL APIのスキーマとリゾルバー
+//! - [`health`] - ヘルスチェック（生存・準備状態・詳細）
+//! - [`http_cache`] - GETで受け取ったGraphQLクエリのHTTPキャッシュ
+//! - [`jobs`] - スクレイピング・翻訳のバックグラウンドジョブ
+//! - [`locale`] - レスポンスの言語（`Accept-Language`と`lang`引数）
+//! - [`metrics`] - Prometheusのメトリクス
+//! - [`persisted_queries`] - GraphQLの永続化クエリ（APQ）
+//! - [`rate_limit`] - GraphQLエンドポイント・REST API・フィードのレート制限
+//! - [`rest`] - REST API（v1）とOpenAPIドキュメント
 //! - [`scraper`] - RSSフィード解析とニュース分類
 //!
 //! ## 使用例
 //!
 //! ```no_run
+//! use std::sync::Arc;
+//!
 //! use nba_trade_scraper::graphql::create_schema;
 //! use nba_trade_scraper::scraper::RssParser;
-//! use nba_trade_scraper::db::connection::create_pool;
+//! use nba_trade_scraper::db::{connection::create_pool, AuthStore, NewsStore};
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -27,7 +41,10 @@
 //!     let pool = create_pool().await.unwrap();
 //!
 //!     // GraphQLスキーマの作成
-//!     let schema = create_schema(pool);
+//!     let schema = create_schema(
+//!         Arc::new(NewsStore::new(pool.clone())),
+//!         Arc::new(AuthStore::new(pool)),
+//!     );
 //!
 //!     // RSSフィードの解析
 //!     let parser = RssParser::new();
@@ -35,61 +52,286 @@
 //! }
 //! ```
 
+/// 認証と認可
+pub mod auth;
+
 /// データベース関連の機能
 pub mod db;
 
+/// エラーの種類とGraphQLのエラーコード
+pub mod errors;
+
+/// RSS・Atomフィードの配信
+pub mod feeds;
+
 /// GraphQL APIの実装
 pub mod graphql;
 
+/// ヘルスチェック
+pub mod health;
+
+/// GETで受け取ったGraphQLクエリのHTTPキャッシュ
+pub mod http_cache;
+
+/// スクレイピング・翻訳のバックグラウンドジョブ
+pub mod jobs;
+
+/// レスポンスの言語
+pub mod locale;
+
+/// Prometheusのメトリクス
+pub mod metrics;
+
+/// GraphQLの永続化クエリ（APQ）
+pub mod persisted_queries;
+
+/// レート制限
+pub mod rate_limit;
+
+/// REST API（v1）
+pub mod rest;
+
 /// RSSスクレイピング機能
 pub mod scraper;
 
 /// スケジューラー機能
 pub mod scheduler;
 
+/// グレースフルシャットダウン
+pub mod shutdown;
+
 /// ユーティリティ関数
 pub mod utils;
 
 /// サービス層（翻訳など）
 pub mod services;
 
-use async_graphql::{EmptySubscription, Schema};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
-use axum::{routing::get, Router};
-use sqlx::postgres::PgPool;
-use tower_http::cors::{Any, CorsLayer};
+use std::sync::Arc;
+
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse};
+use axum::{
+    extract::{ws::WebSocketUpgrade, Extension, Query, RawQuery},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::warn;
+
+use auth::{Authenticator, Principal};
+use db::{SharedAuthRepository, SharedNewsRepository};
+use rate_limit::RateLimiter;
+use services::image_proxy::{
+    ImageProxy, ImageProxyConfig, ImageProxyError, DEFAULT_THUMBNAIL_WIDTH, IMAGE_PROXY_PATH,
+    PLACEHOLDER_SVG,
+};
+use shutdown::BackgroundTasks;
 
 /// アプリケーションの作成
-pub fn create_app(pool: PgPool) -> Router {
-    let schema = graphql::create_schema(pool);
+///
+/// ニュースの保存先は`repository`、APIキーと監査ログの保存先は`auth`で
+/// 差し替えられます。テストやデモでは[`db::InMemoryNewsStore`]と
+/// [`db::InMemoryAuthStore`]を渡すとデータベースなしで起動できます。
+///
+/// GraphQLエンドポイントは[`auth::authenticate`]で認証し
+/// （JWTを受け付けるには環境変数`JWT_SECRET`を設定）、
+/// [`rate_limit::limit_requests`]でクライアントごとに流量を制限します。
+/// REST API（[`rest::API_V1_PATH`]の下）も同じ認証とレート制限を通ります。
+/// フィード（[`feeds::FEEDS_PATH`]の下）はフィードリーダーから読めるよう認証なしで、
+/// レート制限だけを通ります。ヘルスチェック（[`health`]）と
+/// メトリクス（[`metrics::METRICS_PATH`]）は認証・レート制限なしです。
+pub fn create_app(repository: SharedNewsRepository, auth: SharedAuthRepository) -> Router {
+    create_app_with_tasks(repository, auth, BackgroundTasks::default())
+}
+
+/// アプリケーションの作成（バックグラウンドのジョブを`tasks`で実行）
+///
+/// サーバーの終了時に[`BackgroundTasks::drain`]で実行中のジョブを待つときに使います。
+pub fn create_app_with_tasks(
+    repository: SharedNewsRepository,
+    auth: SharedAuthRepository,
+    tasks: BackgroundTasks,
+) -> Router {
+    let authenticator = Arc::new(Authenticator::from_env(auth.clone()));
+    let limiter = Arc::new(RateLimiter::from_env());
+    let rest = rest::routes(repository.clone())
+        .route_layer(middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit::limit_rest_requests,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            authenticator.clone(),
+            auth::authenticate,
+        ));
+    let feeds = feeds::routes(repository.clone(), feeds::FeedConfig::from_env()).route_layer(
+        middleware::from_fn_with_state(limiter.clone(), rate_limit::limit_rest_requests),
+    );
+    let health = health::routes(repository.clone(), health::HealthConfig::from_env());
+    let metrics = metrics::routes(repository.clone());
+    let schema = graphql::create_schema_with_config(
+        repository,
+        auth,
+        graphql::SchemaConfig::from_env(),
+        tasks,
+    );
+    let image_proxy = Arc::new(ImageProxy::new(ImageProxyConfig::from_env()));
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    let graphql = Router::new()
+        .route("/", get(graphql_get).post(graphql_handler))
+        .route("/ws", get(graphql_ws))
+        .route_layer(middleware::from_fn_with_state(
+            limiter,
+            rate_limit::limit_requests,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            authenticator,
+            auth::authenticate,
+        ));
 
     Router::new()
-        .route("/", get(graphiql).post(graphql_handler))
+        .merge(graphql)
+        .nest(rest::API_V1_PATH, rest)
+        .nest(feeds::FEEDS_PATH, feeds)
+        .merge(health)
+        .merge(metrics)
         .route("/health", get(health_check))
-        .layer(cors)
-        .layer(axum::extract::Extension(schema))
+        .route("/health/live", get(health_check))
+        .route(IMAGE_PROXY_PATH, get(image_thumbnail))
+        .layer(cors_layer(&allowed_origins_from_env()))
+        .layer(Extension(schema))
+        .layer(Extension(image_proxy))
+}
+
+/// `CORS_ALLOWED_ORIGINS`が未設定のときに許可するオリジン（開発用のフロントエンド）
+const DEFAULT_ALLOWED_ORIGINS: &str = "http://localhost:3000,http://localhost:8080";
+
+/// 環境変数`CORS_ALLOWED_ORIGINS`（カンマ区切り）から許可するオリジンを読み込む
+fn allowed_origins_from_env() -> Vec<String> {
+    std::env::var("CORS_ALLOWED_ORIGINS")
+        .unwrap_or_else(|_| DEFAULT_ALLOWED_ORIGINS.to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 許可したオリジンからのリクエストだけを受け付けるCORSレイヤー
+///
+/// `*`を含む場合はすべてのオリジンを許可します。
+fn cors_layer(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(origin)
+                .map_err(|_| warn!("Ignoring invalid CORS origin: {}", origin))
+                .ok()
+        }))
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_headers(Any)
 }
 
 async fn graphql_handler(
-    schema: axum::extract::Extension<Schema<graphql::Query, graphql::Mutation, EmptySubscription>>,
+    Extension(schema): Extension<graphql::AppSchema>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
+    graphql::execute_post(&schema, principal.map(|p| p.0), &headers, req.into_inner())
+        .await
+        .into()
+}
+
+/// サブスクリプション（graphql-ws）のWebSocket接続を受け付ける
+async fn graphql_ws(
+    Extension(schema): Extension<graphql::AppSchema>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    graphql::subscription_response(schema, &headers, protocol, upgrade)
+}
+
+/// クエリ文字列があればGraphQLリクエストとして実行し、なければGraphiQLを返す
+async fn graphql_get(
+    Extension(schema): Extension<graphql::AppSchema>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
+    RawQuery(query): RawQuery,
+) -> Response {
+    match query {
+        Some(query) => {
+            http_cache::execute_get(&schema, principal.map(|p| p.0), &query, &headers).await
+        }
+        None => graphiql().await.into_response(),
+    }
 }
 
 async fn graphiql() -> axum::response::Html<String> {
     axum::response::Html(
         async_graphql::http::GraphiQLSource::build()
             .endpoint("/")
+            .subscription_endpoint("/ws")
             .finish(),
     )
 }
 
+#[derive(Debug, Deserialize)]
+struct ThumbnailParams {
+    /// 元画像のURL
+    url: String,
+    /// 希望するサムネイル幅
+    w: Option<u32>,
+}
+
+/// 外部画像を縮小・キャッシュして返す
+///
+/// 取得や変換に失敗した場合は、フロントエンドで壊れた画像が表示されないよう
+/// プレースホルダー画像を返します。
+async fn image_thumbnail(
+    Extension(proxy): Extension<Arc<ImageProxy>>,
+    Query(params): Query<ThumbnailParams>,
+) -> Response {
+    let width = params.w.unwrap_or(DEFAULT_THUMBNAIL_WIDTH);
+    let result = proxy.thumbnail(&params.url, width).await;
+    thumbnail_response(&params.url, result)
+}
+
+/// サムネイルの取得結果をレスポンスにする（失敗したらプレースホルダー画像）
+fn thumbnail_response(url: &str, result: Result<Vec<u8>, ImageProxyError>) -> Response {
+    match result {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (header::CACHE_CONTROL, "public, max-age=86400"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) if e.is_client_error() => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        Err(e) => {
+            warn!("Serving placeholder for {}: {}", url, e);
+            (
+                [
+                    (header::CONTENT_TYPE, "image/svg+xml"),
+                    (header::CACHE_CONTROL, "public, max-age=300"),
+                ],
+                PLACEHOLDER_SVG,
+            )
+                .into_response()
+        }
+    }
+}
+
+/// プロセスが応答するかだけを返す（データベースなどは[`health`]で確かめる）
 async fn health_check() -> axum::Json<serde_json::Value> {
     axum::Json(serde_json::json!({
         "status": "healthy",
@@ -122,15 +364,75 @@ mod tests {
         assert!(content.contains("</html>"));
     }
 
+    fn test_image_proxy() -> Extension<Arc<ImageProxy>> {
+        Extension(Arc::new(ImageProxy::new(ImageProxyConfig {
+            cache_dir: std::env::temp_dir().join("image-proxy-handler-test"),
+            ..ImageProxyConfig::default()
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_image_thumbnail_rejects_internal_hosts() {
+        let response = image_thumbnail(
+            test_image_proxy(),
+            Query(ThumbnailParams {
+                url: "http://127.0.0.1/secret.png".to_string(),
+                w: None,
+            }),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[test]
-    fn test_cors_configuration() {
-        // CORSレイヤーが正しく設定されることを確認
-        let _cors = CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods(Any)
-            .allow_headers(Any);
-
-        // CORSレイヤーの設定を確認（インスタンス化できることを確認）
-        // Test passes
+    fn test_image_thumbnail_serves_placeholder_for_broken_images() {
+        // ネットワークに依存しないよう、取得に失敗した結果から直接レスポンスを作る
+        let response = thumbnail_response(
+            "https://cdn.example.com/missing.jpg",
+            Err(ImageProxyError::FetchFailed(
+                "HTTP 404 Not Found".to_string(),
+            )),
+        );
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/svg+xml"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_only_configured_origins() {
+        use tower::util::ServiceExt;
+
+        let app = Router::new()
+            .route("/health", get(health_check))
+            .layer(cors_layer(&["https://nba.example.com".to_string()]));
+        let request = |origin: &str| {
+            axum::http::Request::builder()
+                .uri("/health")
+                .header("origin", origin)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("https://nba.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response
+                .headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "https://nba.example.com"
+        );
+
+        let response = app.oneshot(request("https://evil.example")).await.unwrap();
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
     }
 }
@@ -0,0 +1,137 @@
+//! レスポンスの言語
+//!
+//! 記事のタイトル・説明文、チーム・選手の名前、カテゴリーの表示名は、フィールドの
+//! `lang`引数、なければリクエストの`Accept-Language`ヘッダーで選んだ言語で返します。
+//! どちらもなければ英語です。
+//!
+//! 日本語が選ばれても翻訳がまだない項目は英語の原文に戻します（フィールドごとに判定
+//! するため、タイトルだけ翻訳済みなら説明文は英語になります）。英語から日本語への
+//! 翻訳はすべて機械翻訳です。
+
+use axum::http::{header, HeaderMap};
+
+/// 対応している言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    /// 英語（記事の原文）
+    #[default]
+    En,
+    /// 日本語（機械翻訳）
+    Ja,
+}
+
+impl Locale {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+
+    /// 言語タグ（`ja-JP`など）の主言語から判定する
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if primary.eq_ignore_ascii_case("ja") {
+            Some(Locale::Ja)
+        } else {
+            None
+        }
+    }
+
+    /// `Accept-Language`の値から、対応している言語のうち最も優先度の高いものを選ぶ
+    ///
+    /// 優先度（`q`）が同じなら先に書かれたものを選びます。`q=0`は除外です。
+    pub fn from_accept_language(value: &str) -> Option<Self> {
+        let mut best: Option<(Locale, f32)> = None;
+        for entry in value.split(',') {
+            let mut parts = entry.split(';');
+            let Some(locale) = parts.next().and_then(Locale::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+            if quality > 0.0 && best.map_or(true, |(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    /// リクエストヘッダーから言語を決める（指定がなければ`None`）
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Locale::from_accept_language)
+    }
+}
+
+/// 翻訳があれば翻訳を、なければ原文を選ぶ
+///
+/// 戻り値の2つ目は、返したのが翻訳（機械翻訳）かどうかです。
+pub fn localize<'a>(
+    locale: Locale,
+    original: &'a str,
+    translated: Option<&'a str>,
+) -> (&'a str, bool) {
+    match (locale, translated) {
+        (Locale::Ja, Some(text)) if !text.trim().is_empty() => (text, true),
+        _ => (original, false),
+    }
+}
+
+/// カテゴリーの表示名（知らないカテゴリーはそのまま返す）
+pub fn category_label(category: &str, locale: Locale) -> &str {
+    match (locale, category) {
+        (Locale::Ja, "Trade") => "トレード",
+        (Locale::Ja, "Signing") => "契約",
+        (Locale::Ja, "Other") => "その他",
+        _ => category,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept_language() {
+        assert_eq!(
+            Locale::from_accept_language("ja-JP,ja;q=0.9,en-US;q=0.8,en;q=0.7"),
+            Some(Locale::Ja)
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, en;q=0.5, ja;q=0.4"),
+            Some(Locale::En)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.3, ja;q=0.8"),
+            Some(Locale::Ja)
+        );
+        assert_eq!(Locale::from_accept_language("ja;q=0, *"), None);
+        assert_eq!(Locale::from_accept_language("de-DE"), None);
+    }
+
+    #[test]
+    fn test_localize_falls_back_to_original() {
+        assert_eq!(
+            localize(Locale::Ja, "Lakers trade", Some("レイカーズがトレード")),
+            ("レイカーズがトレード", true)
+        );
+        assert_eq!(
+            localize(Locale::Ja, "Lakers trade", None),
+            ("Lakers trade", false)
+        );
+        assert_eq!(
+            localize(Locale::En, "Lakers trade", Some("レイカーズがトレード")),
+            ("Lakers trade", false)
+        );
+
+        assert_eq!(category_label("Signing", Locale::Ja), "契約");
+        assert_eq!(category_label("Signing", Locale::En), "Signing");
+    }
+}
@@ -1,7 +1,20 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
 use axum::serve;
-use nba_trade_scraper::{create_app, db::connection::create_pool};
+use nba_trade_scraper::{
+    create_app_with_tasks,
+    db::{
+        connection::create_pool, store::MIGRATOR, AuthStore, InMemoryAuthStore, InMemoryNewsStore,
+        NewsStore, SharedAuthRepository, SharedNewsRepository,
+    },
+    jobs::JobRunner,
+    scheduler::{create_scheduler, SchedulerConfig},
+    shutdown::{shutdown_signal, BackgroundTasks, ShutdownConfig},
+};
 use tokio::net::TcpListener;
-use tracing::{info, Level};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
 #[tokio::main]
@@ -14,21 +27,97 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Starting GraphQL server...");
 
-    // データベース接続の初期化
-    let pool = create_pool().await?;
+    // NEWS_STORE=memory の場合はデータベースなしのデモモードで起動
+    let (repository, auth): (SharedNewsRepository, SharedAuthRepository) =
+        if std::env::var("NEWS_STORE").as_deref() == Ok("memory") {
+            warn!("Using in-memory news store; data will be lost on shutdown");
+            // APIキーは登録できないため、更新操作にはJWT（JWT_SECRET）を使う
+            (
+                Arc::new(InMemoryNewsStore::new()),
+                Arc::new(InMemoryAuthStore::new()),
+            )
+        } else {
+            // データベース接続の初期化
+            let pool = create_pool().await?;
+
+            // PostgreSQLマイグレーションを実行
+            info!("Running PostgreSQL migrations...");
+            MIGRATOR.run(&pool).await?;
+
+            info!("Database initialized");
+
+            // 他のインスタンスでの保存・翻訳もサブスクリプションに配信する
+            let store = NewsStore::new(pool.clone());
+            store.spawn_event_listener();
+            (Arc::new(store), Arc::new(AuthStore::new(pool)))
+        };
 
-    // PostgreSQLマイグレーションを実行
-    info!("Running PostgreSQL migrations...");
-    sqlx::migrate!("./migrations_postgres").run(&pool).await?;
+    // 終了時に実行中のスクレイピング・翻訳ジョブを待つ
+    let tasks = BackgroundTasks::new();
 
-    info!("Database initialized");
+    // EMBEDDED_SCHEDULER=true の場合は、定期ジョブ（schedulerバイナリと同じもの）を
+    // このプロセスで実行する（接続プールはサーバーと共有）
+    let mut scheduler = if std::env::var("EMBEDDED_SCHEDULER").as_deref() == Ok("true") {
+        let runner = JobRunner::new(repository.clone()).with_tasks(tasks.clone());
+        let scheduler = create_scheduler(runner, SchedulerConfig::from_env()).await?;
+        scheduler.start().await?;
+        info!("Embedded scheduler started");
+        Some(scheduler)
+    } else {
+        None
+    };
 
-    let app = create_app(pool);
+    let app = create_app_with_tasks(repository, auth, tasks.clone());
 
     info!("GraphQL playground available at http://localhost:8000");
+    info!("REST API (OpenAPI) available at http://localhost:8000/api/v1/openapi.json");
 
     let listener = TcpListener::bind("0.0.0.0:8000").await?;
-    serve(listener, app).await?;
+    let (stop_accepting, stopped) = oneshot::channel::<()>();
+    let mut server = tokio::spawn(async move {
+        // 未認証クライアントのレート制限に接続元のIPアドレスを使う
+        serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async {
+            let _ = stopped.await;
+        })
+        .await
+    });
+
+    tokio::select! {
+        result = &mut server => {
+            result??;
+            return Ok(());
+        }
+        _ = shutdown_signal() => {}
+    }
+
+    // 新しい接続とスケジュールされたジョブの受け付けを止め、
+    // 処理中のリクエストと実行中のジョブを期限まで待つ
+    let config = ShutdownConfig::from_env();
+    let deadline = config.deadline();
+    info!("Shutting down (waiting up to {:?})...", config.timeout);
+    let _ = stop_accepting.send(());
+    if let Some(scheduler) = scheduler.as_mut() {
+        if let Err(e) = scheduler.shutdown().await {
+            error!("Failed to stop the embedded scheduler: {}", e);
+        }
+    }
+
+    // サブスクリプションのWebSocketは閉じられるまで残るので、期限を過ぎたら待たない
+    match tokio::time::timeout_at(deadline, server).await {
+        Ok(result) => result??,
+        Err(_) => warn!("In-flight connections did not close before the shutdown deadline"),
+    }
+    if !tasks.drain(deadline).await {
+        warn!(
+            "{} running jobs did not finish before the shutdown deadline",
+            tasks.len()
+        );
+    }
+    info!("Server stopped");
 
     Ok(())
 }
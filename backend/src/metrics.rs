@@ -0,0 +1,542 @@
+//! Prometheusのメトリクス
+//!
+//! スクレイピング・翻訳・GraphQL・ジョブの数と所要時間をプロセス全体で集計し、
+//! `/metrics`でPrometheusのテキスト形式で返します（認証・レート制限なし）。
+//! 記録は各処理から[`metrics()`]を通して行います。
+//!
+//! | メトリクス | ラベル | 内容 |
+//! |-----------|--------|------|
+//! | `nba_feed_items_fetched_total` | `source`・`category` | フィードから取得した記事 |
+//! | `nba_feed_fetch_duration_seconds` | `source`・`outcome` | フィードの取得にかかった時間 |
+//! | `nba_feed_errors_total` | `source`・`stage` | フィードの取得（`fetch`）・解析（`parse`）の失敗 |
+//! | `nba_news_items_saved_total` | `source`・`outcome` | 保存した記事（`saved`・`updated`・`skipped`・`error`） |
+//! | `nba_translation_requests_total` | `outcome` | 翻訳サービスの呼び出し |
+//! | `nba_translation_characters_total` | | 翻訳した文字数 |
+//! | `nba_translation_errors_total` | `code` | 翻訳のエラー（`TRANSLATION_RATE_LIMITED`など） |
+//! | `nba_translation_duration_seconds` | | 翻訳にかかった時間 |
+//! | `nba_graphql_request_duration_seconds` | `operation`・`status` | GraphQLのリクエストの処理時間 |
+//! | `nba_job_duration_seconds` | `job`・`outcome` | スケジューラー・バックグラウンドジョブの実行時間 |
+//! | `nba_db_pool_connections` | `state` | 接続プールの使用中（`active`）・待機中（`idle`）の接続 |
+//! | `nba_db_pool_max_connections` | | 接続プールの上限 |
+//!
+//! GraphQLの`operation`はクライアントが付けた操作名のため、種類が
+//! [`MAX_OPERATION_LABELS`]を超えたら以降の新しい名前は`other`にまとめます。
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextRequest,
+};
+use async_graphql::Response as GraphQLResponse;
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::db::{PoolStatus, SharedNewsRepository, SourceCounts};
+use crate::errors::{error_code, translation_error_code, ErrorCode, ErrorStage};
+use crate::scraper::models::NewsItem;
+use crate::services::TranslationError;
+
+/// メトリクスのパス
+pub const METRICS_PATH: &str = "/metrics";
+
+/// GraphQLの操作名のラベルの種類の上限
+pub const MAX_OPERATION_LABELS: usize = 100;
+
+/// 操作名のないGraphQLリクエストのラベル
+const ANONYMOUS_OPERATION: &str = "anonymous";
+
+/// 解析・検証で失敗し、実行されなかったGraphQLリクエストのラベル
+const INVALID_OPERATION: &str = "invalid";
+
+/// 種類が上限を超えた操作名のラベル
+const OTHER_OPERATION: &str = "other";
+
+/// 外部サービス（フィード・翻訳）の呼び出しにかかる時間のバケット（秒）
+const EXTERNAL_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// ジョブの実行時間のバケット（秒）
+const JOB_BUCKETS: &[f64] = &[0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+/// プロセス全体のメトリクス
+pub struct Metrics {
+    registry: Registry,
+    feed_items: IntCounterVec,
+    feed_fetch_duration: HistogramVec,
+    feed_errors: IntCounterVec,
+    saved_items: IntCounterVec,
+    translation_requests: IntCounterVec,
+    translation_characters: IntCounter,
+    translation_errors: IntCounterVec,
+    translation_duration: Histogram,
+    graphql_duration: HistogramVec,
+    job_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+    /// ラベルに使ったGraphQLの操作名
+    operations: Mutex<HashSet<String>>,
+}
+
+/// プロセス全体のメトリクス
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(registry: &Registry, metric: T) -> T {
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("metric names are unique");
+    metric
+}
+
+fn counter_vec(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    let metric = IntCounterVec::new(Opts::new(name, help), labels).expect("valid metric");
+    register(registry, metric)
+}
+
+fn histogram_vec(
+    registry: &Registry,
+    name: &str,
+    help: &str,
+    buckets: &[f64],
+    labels: &[&str],
+) -> HistogramVec {
+    let opts = HistogramOpts::new(name, help).buckets(buckets.to_vec());
+    register(
+        registry,
+        HistogramVec::new(opts, labels).expect("valid metric"),
+    )
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        Self {
+            feed_items: counter_vec(
+                &registry,
+                "nba_feed_items_fetched_total",
+                "News items fetched from RSS feeds",
+                &["source", "category"],
+            ),
+            feed_fetch_duration: histogram_vec(
+                &registry,
+                "nba_feed_fetch_duration_seconds",
+                "Time spent fetching and parsing an RSS feed",
+                EXTERNAL_BUCKETS,
+                &["source", "outcome"],
+            ),
+            feed_errors: counter_vec(
+                &registry,
+                "nba_feed_errors_total",
+                "RSS feeds that could not be fetched or parsed",
+                &["source", "stage"],
+            ),
+            saved_items: counter_vec(
+                &registry,
+                "nba_news_items_saved_total",
+                "Scraped news items by save outcome",
+                &["source", "outcome"],
+            ),
+            translation_requests: counter_vec(
+                &registry,
+                "nba_translation_requests_total",
+                "Calls to the translation service",
+                &["outcome"],
+            ),
+            translation_characters: register(
+                &registry,
+                IntCounter::new(
+                    "nba_translation_characters_total",
+                    "Characters sent to the translation service",
+                )
+                .expect("valid metric"),
+            ),
+            translation_errors: counter_vec(
+                &registry,
+                "nba_translation_errors_total",
+                "Failed calls to the translation service by error code",
+                &["code"],
+            ),
+            translation_duration: register(
+                &registry,
+                Histogram::with_opts(
+                    HistogramOpts::new(
+                        "nba_translation_duration_seconds",
+                        "Time spent in the translation service",
+                    )
+                    .buckets(EXTERNAL_BUCKETS.to_vec()),
+                )
+                .expect("valid metric"),
+            ),
+            graphql_duration: histogram_vec(
+                &registry,
+                "nba_graphql_request_duration_seconds",
+                "GraphQL request latency by operation name",
+                prometheus::DEFAULT_BUCKETS,
+                &["operation", "status"],
+            ),
+            job_duration: histogram_vec(
+                &registry,
+                "nba_job_duration_seconds",
+                "Duration of scheduled and background jobs",
+                JOB_BUCKETS,
+                &["job", "outcome"],
+            ),
+            db_pool_connections: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new(
+                        "nba_db_pool_connections",
+                        "Database pool connections by state",
+                    ),
+                    &["state"],
+                )
+                .expect("valid metric"),
+            ),
+            db_pool_max_connections: register(
+                &registry,
+                IntGauge::new(
+                    "nba_db_pool_max_connections",
+                    "Maximum number of database pool connections",
+                )
+                .expect("valid metric"),
+            ),
+            operations: Mutex::new(HashSet::new()),
+            registry,
+        }
+    }
+
+    /// フィードの取得結果を記録する
+    pub fn record_fetch(
+        &self,
+        source: &str,
+        duration: Duration,
+        result: &anyhow::Result<Vec<NewsItem>>,
+    ) {
+        let outcome = match result {
+            Ok(items) => {
+                for item in items {
+                    self.feed_items
+                        .with_label_values(&[source, item.category.as_str()])
+                        .inc();
+                }
+                "success"
+            }
+            Err(e) => {
+                let stage = if error_code(e) == ErrorCode::FeedParseFailed {
+                    ErrorStage::Parse
+                } else {
+                    ErrorStage::Fetch
+                };
+                self.feed_errors
+                    .with_label_values(&[source, stage.as_str()])
+                    .inc();
+                "error"
+            }
+        };
+        self.feed_fetch_duration
+            .with_label_values(&[source, outcome])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// 1つのソースの記事を保存した件数を記録する
+    pub fn record_save(&self, source: &str, counts: &SourceCounts) {
+        for (outcome, count) in [
+            ("saved", counts.saved),
+            ("updated", counts.updated),
+            ("skipped", counts.skipped),
+            ("error", counts.errors),
+        ] {
+            self.saved_items
+                .with_label_values(&[source, outcome])
+                .inc_by(count as u64);
+        }
+    }
+
+    /// 翻訳サービスの呼び出しを記録する
+    pub fn record_translation(
+        &self,
+        characters: usize,
+        duration: Duration,
+        result: &Result<String, TranslationError>,
+    ) {
+        self.translation_characters.inc_by(characters as u64);
+        self.translation_duration.observe(duration.as_secs_f64());
+        let outcome = match result {
+            Ok(_) => "success",
+            Err(e) => {
+                self.translation_errors
+                    .with_label_values(&[translation_error_code(e).as_str()])
+                    .inc();
+                "error"
+            }
+        };
+        self.translation_requests
+            .with_label_values(&[outcome])
+            .inc();
+    }
+
+    /// GraphQLのリクエストの処理時間を記録する
+    pub fn record_graphql(&self, operation: &str, duration: Duration, succeeded: bool) {
+        let operation = self.operation_label(operation);
+        let status = if succeeded { "ok" } else { "error" };
+        self.graphql_duration
+            .with_label_values(&[operation.as_str(), status])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// ジョブの実行時間を記録する
+    pub fn record_job(&self, job: &str, duration: Duration, succeeded: bool) {
+        let outcome = if succeeded { "success" } else { "error" };
+        self.job_duration
+            .with_label_values(&[job, outcome])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// 操作名をラベルにする（種類が上限を超えたら`other`）
+    fn operation_label(&self, operation: &str) -> String {
+        let mut operations = self.operations.lock().unwrap_or_else(|e| e.into_inner());
+        if operations.contains(operation) {
+            return operation.to_string();
+        }
+        if operations.len() >= MAX_OPERATION_LABELS {
+            return OTHER_OPERATION.to_string();
+        }
+        operations.insert(operation.to_string());
+        operation.to_string()
+    }
+
+    /// Prometheusのテキスト形式で出力する（接続プールの状態はこのときに読む）
+    pub fn render(&self, pool: Option<PoolStatus>) -> String {
+        if let Some(pool) = pool {
+            let active = pool.size.saturating_sub(pool.idle);
+            self.db_pool_connections
+                .with_label_values(&["active"])
+                .set(i64::from(active));
+            self.db_pool_connections
+                .with_label_values(&["idle"])
+                .set(i64::from(pool.idle));
+            self.db_pool_max_connections.set(i64::from(pool.max_size));
+        }
+
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// GraphQLのリクエストの処理時間を操作名ごとに記録する拡張
+pub struct GraphQLMetrics;
+
+impl ExtensionFactory for GraphQLMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQLMetricsExtension {
+            operation: Mutex::new(None),
+        })
+    }
+}
+
+/// 1リクエストごとの状態（実行した操作の名前）
+struct GraphQLMetricsExtension {
+    operation: Mutex<Option<String>>,
+}
+
+#[async_trait]
+impl Extension for GraphQLMetricsExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> GraphQLResponse {
+        let started = Instant::now();
+        let response = next.run(ctx).await;
+        let operation = self
+            .operation
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_else(|| INVALID_OPERATION.to_string());
+        metrics().record_graphql(&operation, started.elapsed(), response.is_ok());
+        response
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> GraphQLResponse {
+        *self.operation.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(operation_name.unwrap_or(ANONYMOUS_OPERATION).to_string());
+        next.run(ctx, operation_name).await
+    }
+}
+
+/// メトリクスのルーター
+pub fn routes(repository: SharedNewsRepository) -> Router {
+    Router::new()
+        .route(METRICS_PATH, get(render_metrics))
+        .with_state(repository)
+}
+
+async fn render_metrics(State(repository): State<SharedNewsRepository>) -> Response {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        metrics().render(repository.pool_status()),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::news_item;
+    use crate::errors::AppError;
+
+    /// 出力からラベルの組み合わせに一致する行の値を探す
+    fn sample(output: &str, name: &str, labels: &[(&str, &str)]) -> f64 {
+        output
+            .lines()
+            .filter(|line| line.starts_with(name) && !line.starts_with('#'))
+            .find(|line| {
+                labels
+                    .iter()
+                    .all(|(key, value)| line.contains(&format!("{}=\"{}\"", key, value)))
+            })
+            .and_then(|line| line.rsplit(' ').next())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0.0)
+    }
+
+    #[test]
+    fn test_records_fetches_and_saves() {
+        let metrics = Metrics::new();
+        let items = vec![
+            news_item("a", "Lakers trade"),
+            news_item("b", "Lakers trade"),
+        ];
+        metrics.record_fetch("ESPN", Duration::from_millis(120), &Ok(items));
+        metrics.record_fetch(
+            "RealGM",
+            Duration::from_millis(80),
+            &Err(AppError::FeedParse("unexpected EOF".to_string()).into()),
+        );
+        metrics.record_save(
+            "ESPN",
+            &SourceCounts {
+                saved: 1,
+                skipped: 1,
+                ..SourceCounts::default()
+            },
+        );
+
+        let output = metrics.render(None);
+        let labels = [("source", "ESPN"), ("category", "Trade")];
+        assert_eq!(
+            sample(&output, "nba_feed_items_fetched_total", &labels),
+            2.0
+        );
+        let labels = [("source", "RealGM"), ("stage", "parse")];
+        assert_eq!(sample(&output, "nba_feed_errors_total", &labels), 1.0);
+        let labels = [("source", "ESPN"), ("outcome", "success")];
+        assert_eq!(
+            sample(&output, "nba_feed_fetch_duration_seconds_count", &labels),
+            1.0
+        );
+        let labels = [("source", "ESPN"), ("outcome", "skipped")];
+        assert_eq!(sample(&output, "nba_news_items_saved_total", &labels), 1.0);
+    }
+
+    #[test]
+    fn test_records_translations_and_pool() {
+        let metrics = Metrics::new();
+        metrics.record_translation(11, Duration::from_millis(200), &Ok("こんにちは".into()));
+        metrics.record_translation(
+            5,
+            Duration::from_millis(10),
+            &Err(TranslationError::RateLimitExceeded),
+        );
+
+        let output = metrics.render(Some(PoolStatus {
+            size: 4,
+            idle: 1,
+            max_size: 10,
+        }));
+        assert_eq!(
+            sample(&output, "nba_translation_characters_total", &[]),
+            16.0
+        );
+        let labels = [("outcome", "error")];
+        assert_eq!(
+            sample(&output, "nba_translation_requests_total", &labels),
+            1.0
+        );
+        let labels = [("code", "TRANSLATION_RATE_LIMITED")];
+        assert_eq!(
+            sample(&output, "nba_translation_errors_total", &labels),
+            1.0
+        );
+        let labels = [("state", "active")];
+        assert_eq!(sample(&output, "nba_db_pool_connections", &labels), 3.0);
+        assert_eq!(sample(&output, "nba_db_pool_max_connections", &[]), 10.0);
+    }
+
+    #[test]
+    fn test_operation_labels_are_bounded() {
+        let metrics = Metrics::new();
+        for i in 0..MAX_OPERATION_LABELS + 5 {
+            metrics.record_graphql(&format!("Operation{}", i), Duration::from_millis(1), true);
+        }
+        metrics.record_graphql("Operation0", Duration::from_millis(1), false);
+
+        let output = metrics.render(None);
+        let name = "nba_graphql_request_duration_seconds_count";
+        assert_eq!(sample(&output, name, &[("operation", "other")]), 5.0);
+        let labels = [("operation", "Operation0"), ("status", "error")];
+        assert_eq!(sample(&output, name, &labels), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_graphql_extension_labels_operation_name() {
+        use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+        struct Query;
+
+        #[Object]
+        impl Query {
+            async fn value(&self) -> i32 {
+                1
+            }
+        }
+
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(GraphQLMetrics)
+            .finish();
+        let name = "nba_graphql_request_duration_seconds_count";
+        let count = |labels: &[(&str, &str)]| sample(&metrics().render(None), name, labels);
+        let named = [("operation", "MetricsTestValue"), ("status", "ok")];
+        let invalid = [("operation", "invalid"), ("status", "error")];
+        let (named_before, invalid_before) = (count(&named), count(&invalid));
+
+        let response = schema.execute("query MetricsTestValue { value }").await;
+        assert!(response.is_ok());
+        let response = schema.execute("query { value").await;
+        assert!(response.is_err());
+
+        assert_eq!(count(&named), named_before + 1.0);
+        assert!(count(&invalid) >= invalid_before + 1.0);
+    }
+}
@@ -0,0 +1,262 @@
+//! Automatic Persisted Queries（APQ）
+//!
+//! クライアントはクエリ本文の代わりにSHA-256ハッシュだけを送り、サーバーが
+//! 知らないハッシュなら`PersistedQueryNotFound`を返して本文付きで再送してもらいます
+//! （Apollo Clientのpersisted-queriesリンクと同じ手順）。
+//!
+//! 解析済みのクエリはメモリ上のLRUキャッシュに、本文はリポジトリに保存するため、
+//! 再起動後や他のインスタンスでもハッシュだけで実行できます。
+//!
+//! ミューテーションを含むクエリは登録しません。常に本文付きで送られるため、
+//! GETでの実行拒否やミューテーション用のレート制限をすり抜けられません。
+
+use std::sync::Arc;
+
+use async_graphql::extensions::apollo_persisted_queries::{CacheStorage, LruCacheStorage};
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+};
+use async_graphql::parser::parse_query;
+use async_graphql::parser::types::{ExecutableDocument, OperationType};
+use async_graphql::{from_value, Request, ServerError, ServerResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::db::SharedNewsRepository;
+use crate::utils::env_utils::env_parse;
+
+/// 永続化クエリの設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedQueryConfig {
+    /// メモリ上に保持する解析済みクエリの数
+    pub cache_size: usize,
+    /// クエリ本文をリポジトリにも保存する
+    pub persist: bool,
+}
+
+impl Default for PersistedQueryConfig {
+    fn default() -> Self {
+        Self {
+            cache_size: 1000,
+            persist: true,
+        }
+    }
+}
+
+impl PersistedQueryConfig {
+    /// 環境変数から設定を読み込む（未設定の項目はデフォルト値）
+    ///
+    /// - `APQ_CACHE_SIZE`: メモリ上に保持するクエリの数
+    /// - `APQ_PERSIST`: `false`ならリポジトリに保存しない
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            cache_size: env_parse("APQ_CACHE_SIZE").unwrap_or(defaults.cache_size),
+            persist: env_parse("APQ_PERSIST").unwrap_or(defaults.persist),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PersistedQuery {
+    version: i32,
+    #[serde(rename = "sha256Hash")]
+    sha256_hash: String,
+}
+
+/// 永続化クエリを扱うスキーマ拡張
+#[derive(Clone)]
+pub struct PersistedQueries {
+    cache: LruCacheStorage,
+    repository: Option<SharedNewsRepository>,
+}
+
+impl PersistedQueries {
+    pub fn new(config: &PersistedQueryConfig, repository: SharedNewsRepository) -> Self {
+        Self {
+            cache: LruCacheStorage::new(config.cache_size.max(1)),
+            repository: config.persist.then_some(repository),
+        }
+    }
+
+    /// ハッシュから解析済みのクエリを探す（メモリになければリポジトリから読み込む）
+    async fn load(&self, hash: &str) -> ServerResult<Option<ExecutableDocument>> {
+        if let Some(document) = self.cache.get(hash.to_string()).await {
+            return Ok(Some(document));
+        }
+        let Some(repository) = &self.repository else {
+            return Ok(None);
+        };
+
+        match repository.get_persisted_query(hash).await {
+            Ok(Some(query)) => {
+                let document = parse_query(query)?;
+                self.cache.set(hash.to_string(), document.clone()).await;
+                Ok(Some(document))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                // 見つからなかった扱いにして、クライアントに本文付きで再送してもらう
+                warn!("Failed to load persisted query {}: {}", hash, e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn store(&self, hash: String, query: &str, document: ExecutableDocument) {
+        if let Some(repository) = &self.repository {
+            if let Err(e) = repository.save_persisted_query(&hash, query).await {
+                warn!("Failed to save persisted query {}: {}", hash, e);
+            }
+        }
+        self.cache.set(hash, document).await;
+    }
+}
+
+fn contains_mutation(document: &ExecutableDocument) -> bool {
+    document
+        .operations
+        .iter()
+        .any(|(_, operation)| operation.node.ty == OperationType::Mutation)
+}
+
+impl ExtensionFactory for PersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl Extension for PersistedQueries {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let Some(value) = request.extensions.remove("persistedQuery") else {
+            return next.run(ctx, request).await;
+        };
+        let persisted: PersistedQuery = from_value(value).map_err(|_| {
+            ServerError::new("Invalid \"persistedQuery\" extension configuration.", None)
+        })?;
+        if persisted.version != 1 {
+            return Err(ServerError::new(
+                format!(
+                    "Unsupported \"persistedQuery\" version {}; only version 1 is supported.",
+                    persisted.version
+                ),
+                None,
+            ));
+        }
+
+        if request.query.is_empty() {
+            let document = self
+                .load(&persisted.sha256_hash)
+                .await?
+                .ok_or_else(|| ServerError::new("PersistedQueryNotFound", None))?;
+            request.set_parsed_query(document);
+        } else {
+            let hash = format!("{:x}", Sha256::digest(request.query.as_bytes()));
+            if hash != persisted.sha256_hash {
+                return Err(ServerError::new("provided sha does not match query", None));
+            }
+            let document = parse_query(&request.query)?;
+            if !contains_mutation(&document) {
+                self.store(hash, &request.query, document.clone()).await;
+            }
+            request.query.clear();
+            request.set_parsed_query(document);
+        }
+
+        next.run(ctx, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{InMemoryAuthStore, InMemoryNewsStore, NewsRepository};
+    use crate::graphql::create_schema;
+    use async_graphql::Value;
+
+    const QUERY: &str = "{ teams { code } }";
+
+    fn hash(query: &str) -> String {
+        format!("{:x}", Sha256::digest(query.as_bytes()))
+    }
+
+    fn persisted_request(query: &str, hash: &str) -> Request {
+        let mut request = Request::new(query);
+        request.extensions.insert(
+            "persistedQuery".to_string(),
+            Value::from_json(serde_json::json!({ "version": 1, "sha256Hash": hash })).unwrap(),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn test_registers_and_runs_queries_by_hash() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        let schema = create_schema(store.clone(), Arc::new(InMemoryAuthStore::new()));
+
+        // 未登録のハッシュ
+        let result = schema.execute(persisted_request("", &hash(QUERY))).await;
+        assert_eq!(result.errors[0].message, "PersistedQueryNotFound");
+
+        // 本文付きで登録
+        let result = schema.execute(persisted_request(QUERY, &hash(QUERY))).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let result = schema.execute(persisted_request("", &hash(QUERY))).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let json = result.data.into_json().unwrap();
+        assert_eq!(json["teams"].as_array().unwrap().len(), 30);
+
+        // 別のスキーマ（再起動後・他のインスタンス）でもリポジトリから読み込める
+        assert_eq!(
+            store.get_persisted_query(&hash(QUERY)).await.unwrap(),
+            Some(QUERY.to_string())
+        );
+        let schema = create_schema(store, Arc::new(InMemoryAuthStore::new()));
+        let result = schema.execute(persisted_request("", &hash(QUERY))).await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_mismatched_hash() {
+        let schema = create_schema(
+            Arc::new(InMemoryNewsStore::new()),
+            Arc::new(InMemoryAuthStore::new()),
+        );
+
+        let result = schema.execute(persisted_request(QUERY, "0000")).await;
+        assert_eq!(
+            result.errors[0].message,
+            "provided sha does not match query"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mutations_are_not_registered() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        let schema = create_schema(store.clone(), Arc::new(InMemoryAuthStore::new()));
+        let mutation = "mutation { translatePendingNews { id } }";
+
+        // 実行はされる（未認証なので認可エラー）が、登録はされない
+        let result = schema
+            .execute(persisted_request(mutation, &hash(mutation)))
+            .await;
+        assert_ne!(result.errors[0].message, "PersistedQueryNotFound");
+        assert!(store
+            .get_persisted_query(&hash(mutation))
+            .await
+            .unwrap()
+            .is_none());
+
+        let result = schema.execute(persisted_request("", &hash(mutation))).await;
+        assert_eq!(result.errors[0].message, "PersistedQueryNotFound");
+    }
+}
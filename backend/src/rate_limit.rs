@@ -0,0 +1,522 @@
+//! GraphQLエンドポイント・REST API・フィードのレート制限
+//!
+//! クライアントごとにトークンバケットを持ち、クエリとミューテーションを別々に
+//! 制限します（REST API・フィードのリクエストはクエリとして数えます）。クライアントは
+//! 認証済みなら主体（APIキー・JWT）、未認証ならIPアドレスで区別します。
+//!
+//! 上限を超えたリクエストは実行せず、`429 Too Many Requests`と`Retry-After`
+//! ヘッダー、拡張コード`RATE_LIMITED`のGraphQL形式のエラーを返します。
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_graphql::parser::{parse_query, types::DocumentOperations, types::OperationType};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::auth::{self, Principal};
+use crate::errors::ErrorCode;
+use crate::utils::env_utils::env_parse;
+
+/// 1リクエストのボディの最大サイズ（axumのデフォルトと同じ2MB）
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// これを超える数のバケットを持ったら、満タンに戻ったものを捨てる
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// レート制限の対象となる操作の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+}
+
+impl OperationKind {
+    fn plural(self) -> &'static str {
+        match self {
+            OperationKind::Query => "queries",
+            OperationKind::Mutation => "mutations",
+        }
+    }
+}
+
+/// 1種類の操作に対する上限
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// 1分あたりに回復するリクエスト数（0なら無制限）
+    pub per_minute: u32,
+    /// 連続して受け付けるリクエスト数（バケットの容量）
+    pub burst: u32,
+}
+
+impl RateLimit {
+    fn is_unlimited(&self) -> bool {
+        self.per_minute == 0
+    }
+
+    fn capacity(&self) -> f64 {
+        f64::from(self.burst.max(1))
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// レート制限の設定
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub query: RateLimit,
+    pub mutation: RateLimit,
+    /// ロードバランサーが付ける`X-Forwarded-For`の末尾をクライアントのIPとみなす
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            query: RateLimit {
+                per_minute: 120,
+                burst: 60,
+            },
+            mutation: RateLimit {
+                per_minute: 10,
+                burst: 5,
+            },
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// 環境変数から設定を読み込む（未設定の項目はデフォルト値）
+    ///
+    /// - `RATE_LIMIT_QUERIES_PER_MINUTE`・`RATE_LIMIT_QUERY_BURST`: クエリの上限
+    /// - `RATE_LIMIT_MUTATIONS_PER_MINUTE`・`RATE_LIMIT_MUTATION_BURST`: ミューテーションの上限
+    /// - `TRUST_FORWARDED_FOR`: `true`ならX-Forwarded-ForからクライアントのIPを取る
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            query: RateLimit {
+                per_minute: env_parse("RATE_LIMIT_QUERIES_PER_MINUTE")
+                    .unwrap_or(defaults.query.per_minute),
+                burst: env_parse("RATE_LIMIT_QUERY_BURST").unwrap_or(defaults.query.burst),
+            },
+            mutation: RateLimit {
+                per_minute: env_parse("RATE_LIMIT_MUTATIONS_PER_MINUTE")
+                    .unwrap_or(defaults.mutation.per_minute),
+                burst: env_parse("RATE_LIMIT_MUTATION_BURST").unwrap_or(defaults.mutation.burst),
+            },
+            trust_forwarded_for: env_parse("TRUST_FORWARDED_FOR")
+                .unwrap_or(defaults.trust_forwarded_for),
+        }
+    }
+
+    fn limit(&self, kind: OperationKind) -> &RateLimit {
+        match kind {
+            OperationKind::Query => &self.query,
+            OperationKind::Mutation => &self.mutation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    /// `now`時点まで回復させたトークン数
+    fn refilled(&self, limit: &RateLimit, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * limit.tokens_per_second()).min(limit.capacity())
+    }
+}
+
+/// クライアント・操作の種類ごとのトークンバケット
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(String, OperationKind), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 環境変数の設定でレート制限を作る
+    pub fn from_env() -> Self {
+        Self::new(RateLimitConfig::from_env())
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// リクエストを1件消費する
+    ///
+    /// 上限に達している場合は、次に受け付けられるまでの時間を返します。
+    pub fn check(&self, client: &str, kind: OperationKind) -> Result<(), Duration> {
+        self.check_at(client, kind, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, kind: OperationKind, now: Instant) -> Result<(), Duration> {
+        let limit = self.config.limit(kind);
+        if limit.is_unlimited() {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            let config = &self.config;
+            buckets.retain(|(_, kind), bucket| {
+                let limit = config.limit(*kind);
+                bucket.refilled(limit, now) < limit.capacity()
+            });
+        }
+
+        let bucket = buckets.entry((client.to_string(), kind)).or_insert(Bucket {
+            tokens: limit.capacity(),
+            updated_at: now,
+        });
+        bucket.tokens = bucket.refilled(limit, now);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / limit.tokens_per_second();
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+}
+
+/// レート制限のキーにするクライアントの識別子
+///
+/// 認証済みなら主体（例: `api_key:ci`）、未認証なら`ip:<アドレス>`です。
+pub fn client_key(request: &Request, trust_forwarded_for: bool) -> String {
+    if let Some(principal) = request.extensions().get::<Principal>() {
+        return principal.actor.clone();
+    }
+
+    let forwarded = trust_forwarded_for
+        .then(|| forwarded_for(request.headers()))
+        .flatten();
+    let address = forwarded.or_else(|| {
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    });
+
+    format!("ip:{}", address.as_deref().unwrap_or("unknown"))
+}
+
+/// `X-Forwarded-For`の末尾（直前のプロキシが付けたアドレス）
+fn forwarded_for(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .map(str::trim)
+        .find(|address| !address.is_empty())
+        .map(str::to_string)
+}
+
+#[derive(Deserialize)]
+struct OperationRequest {
+    query: Option<String>,
+    #[serde(rename = "operationName")]
+    operation_name: Option<String>,
+}
+
+/// GraphQLリクエストのボディから実行される操作の種類を判定する
+///
+/// 解析できないリクエストはクエリとして扱います（エラーはスキーマが返す）。
+pub fn operation_kind(body: &[u8]) -> OperationKind {
+    match serde_json::from_slice::<OperationRequest>(body) {
+        Ok(request) => request.query.map_or(OperationKind::Query, |query| {
+            operation_kind_of(&query, request.operation_name.as_deref())
+        }),
+        Err(_) => OperationKind::Query,
+    }
+}
+
+/// クエリ本文と操作名から実行される操作の種類を判定する
+pub fn operation_kind_of(query: &str, operation_name: Option<&str>) -> OperationKind {
+    let Ok(document) = parse_query(query) else {
+        return OperationKind::Query;
+    };
+
+    let operation = match &document.operations {
+        DocumentOperations::Single(operation) => Some(operation),
+        DocumentOperations::Multiple(operations) => {
+            operation_name.and_then(|name| operations.get(name))
+        }
+    };
+
+    match operation.map(|operation| operation.node.ty) {
+        Some(OperationType::Mutation) => OperationKind::Mutation,
+        _ => OperationKind::Query,
+    }
+}
+
+/// GraphQLリクエストをレート制限するミドルウェア
+///
+/// 認証ミドルウェア（[`auth::authenticate`]）の内側に置きます。POSTと、
+/// クエリ文字列付きのGET（クエリとして数える）が対象で、GraphiQLの表示や
+/// サブスクリプションの接続は数えません。
+pub async fn limit_requests(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = client_key(&request, limiter.config().trust_forwarded_for);
+    let (kind, request) = if request.method() == Method::POST {
+        let (parts, body) = request.into_parts();
+        let Ok(bytes) = to_bytes(body, MAX_BODY_BYTES).await else {
+            return auth::error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Request body is too large",
+                "PAYLOAD_TOO_LARGE",
+            );
+        };
+        (
+            operation_kind(&bytes),
+            Request::from_parts(parts, Body::from(bytes)),
+        )
+    } else if request.method() == Method::GET && request.uri().query().is_some() {
+        // GETではミューテーションを実行しない
+        (OperationKind::Query, request)
+    } else {
+        return next.run(request).await;
+    };
+
+    if let Err(retry_after) = limiter.check(&client, kind) {
+        warn!("Rate limit exceeded for {} ({})", client, kind.plural());
+        return rate_limited(kind, retry_after);
+    }
+
+    next.run(request).await
+}
+
+/// REST APIのリクエストをレート制限するミドルウェア
+///
+/// REST API（[`crate::rest`]）とフィード（[`crate::feeds`]）は読み取り専用のため、
+/// すべてのリクエストをクエリとして数えます。GraphQLのクエリとは同じバケットを使います。
+pub async fn limit_rest_requests(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = client_key(&request, limiter.config().trust_forwarded_for);
+    if let Err(retry_after) = limiter.check(&client, OperationKind::Query) {
+        warn!("Rate limit exceeded for {} (REST API)", client);
+        return rate_limited(OperationKind::Query, retry_after);
+    }
+
+    next.run(request).await
+}
+
+fn rate_limited(kind: OperationKind, retry_after: Duration) -> Response {
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let body = serde_json::json!({
+        "errors": [{
+            "message": format!(
+                "Rate limit exceeded for {}; retry in {} seconds",
+                kind.plural(),
+                seconds
+            ),
+            "extensions": {
+                "code": ErrorCode::RateLimited.as_str(),
+                "retryAfter": seconds
+            }
+        }]
+    });
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, seconds.to_string())],
+        Json(body),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+
+    fn limiter(query: RateLimit, mutation: RateLimit) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            query,
+            mutation,
+            trust_forwarded_for: false,
+        })
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_refills() {
+        let limiter = limiter(
+            RateLimit {
+                per_minute: 60,
+                burst: 2,
+            },
+            RateLimit {
+                per_minute: 6,
+                burst: 1,
+            },
+        );
+        let start = Instant::now();
+
+        assert!(limiter
+            .check_at("ip:1.2.3.4", OperationKind::Query, start)
+            .is_ok());
+        assert!(limiter
+            .check_at("ip:1.2.3.4", OperationKind::Query, start)
+            .is_ok());
+        let wait = limiter
+            .check_at("ip:1.2.3.4", OperationKind::Query, start)
+            .unwrap_err();
+        assert!(wait <= Duration::from_secs(1));
+
+        // 1秒で1件回復する
+        let later = start + Duration::from_secs(1);
+        assert!(limiter
+            .check_at("ip:1.2.3.4", OperationKind::Query, later)
+            .is_ok());
+        assert!(limiter
+            .check_at("ip:1.2.3.4", OperationKind::Query, later)
+            .is_err());
+
+        // クライアントごとに別のバケット
+        assert!(limiter
+            .check_at("ip:5.6.7.8", OperationKind::Query, later)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_queries_and_mutations_are_limited_separately() {
+        let limiter = limiter(
+            RateLimit {
+                per_minute: 60,
+                burst: 1,
+            },
+            RateLimit {
+                per_minute: 6,
+                burst: 1,
+            },
+        );
+        let now = Instant::now();
+
+        assert!(limiter
+            .check_at("api_key:ci", OperationKind::Mutation, now)
+            .is_ok());
+        let wait = limiter
+            .check_at("api_key:ci", OperationKind::Mutation, now)
+            .unwrap_err();
+        assert_eq!(wait.as_secs(), 10);
+        assert!(limiter
+            .check_at("api_key:ci", OperationKind::Query, now)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_zero_per_minute_disables_limit() {
+        let unlimited = RateLimit {
+            per_minute: 0,
+            burst: 1,
+        };
+        let limiter = limiter(unlimited, unlimited);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter
+                .check_at("ip:unknown", OperationKind::Mutation, now)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_operation_kind() {
+        let body = |query: &str, name: Option<&str>| {
+            serde_json::to_vec(&serde_json::json!({ "query": query, "operationName": name }))
+                .unwrap()
+        };
+
+        assert_eq!(
+            operation_kind(&body("{ tradeNews { id } }", None)),
+            OperationKind::Query
+        );
+        assert_eq!(
+            operation_kind(&body("mutation { scrapeRss { savedCount } }", None)),
+            OperationKind::Mutation
+        );
+
+        let document =
+            "query List { tradeNews { id } } mutation Scrape { scrapeRss { savedCount } }";
+        assert_eq!(
+            operation_kind(&body(document, Some("Scrape"))),
+            OperationKind::Mutation
+        );
+        assert_eq!(
+            operation_kind(&body(document, Some("List"))),
+            OperationKind::Query
+        );
+
+        assert_eq!(operation_kind(b"not json"), OperationKind::Query);
+    }
+
+    #[test]
+    fn test_client_key() {
+        let request = |forwarded: Option<&str>| {
+            let mut builder = Request::builder().uri("/");
+            if let Some(forwarded) = forwarded {
+                builder = builder.header("x-forwarded-for", forwarded);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let mut authenticated = request(None);
+        authenticated
+            .extensions_mut()
+            .insert(Principal::api_key("ci", Role::Editor));
+        assert_eq!(client_key(&authenticated, false), "api_key:ci");
+
+        let mut direct = request(Some("203.0.113.7"));
+        direct
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 54321))));
+        assert_eq!(client_key(&direct, false), "ip:10.0.0.1");
+
+        // 信頼する場合はロードバランサーが付けた末尾のアドレスを使う
+        let proxied = request(Some("198.51.100.1, 203.0.113.7"));
+        assert_eq!(client_key(&proxied, true), "ip:203.0.113.7");
+
+        assert_eq!(client_key(&request(None), true), "ip:unknown");
+    }
+
+    #[test]
+    fn test_rate_limited_response() {
+        let response = rate_limited(OperationKind::Mutation, Duration::from_millis(2500));
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "3");
+    }
+}
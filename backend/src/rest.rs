@@ -0,0 +1,853 @@
+//! REST API（v1）
+//!
+//! GraphQLを使いにくいクライアント（スクリプト、スプレッドシート、チャットボットなど）
+//! 向けの読み取り専用のAPIです。
+//!
+//! - `GET /api/v1/news`: 絞り込み条件付きのニュース一覧（カーソルページング）
+//! - `GET /api/v1/news/{id}`: IDでニュースを取得
+//! - `GET /api/v1/teams`: カンファレンス・ディビジョンで絞り込んだチーム一覧
+//! - `GET /api/v1/stats`: 時・日・週ごとの記事数
+//! - `GET /api/v1/openapi.json`: このAPIのOpenAPI 3ドキュメント
+//!
+//! 絞り込み条件とページングはGraphQLの`news`と同じで、カーソルもGraphQLの
+//! `pageInfo.endCursor`などとそのまま相互に使えます。エラーは認証・レート制限の
+//! ミドルウェアと同じ形式（`errors[].extensions.code`）で、HTTPステータスは
+//! エラーコードから決めます（[`ErrorCode::http_status`]）。
+//!
+//! OpenAPIドキュメントはハンドラーと型の定義から生成します（[`ApiDoc`]）。
+
+use std::collections::HashMap;
+
+use async_graphql::connection::{CursorType, OpaqueCursor};
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::db::{models, repository, stats, NewsCursor, NewsFilter, SharedNewsRepository};
+use crate::errors::{self, AppError, ErrorCode};
+use crate::graphql;
+
+/// REST API（v1）のパス
+pub const API_V1_PATH: &str = "/api/v1";
+
+/// REST API（v1）のルーター（[`API_V1_PATH`]の下に置く）
+pub fn routes(repository: SharedNewsRepository) -> Router {
+    Router::new()
+        .route("/news", get(list_news))
+        .route("/news/{id}", get(get_news))
+        .route("/teams", get(list_teams))
+        .route("/stats", get(news_stats))
+        .route("/openapi.json", get(openapi_json))
+        .with_state(repository)
+}
+
+/// REST API（v1）のOpenAPIドキュメント
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "NBA Trade News REST API",
+        version = "1.0.0",
+        description = "NBAのトレード・契約ニュースを取得する読み取り専用のAPI。\
+            絞り込み条件とページングはGraphQLの`news`と同じです。"
+    ),
+    paths(list_news, get_news, list_teams, news_stats),
+    tags(
+        (name = "news", description = "ニュース"),
+        (name = "teams", description = "チーム"),
+        (name = "stats", description = "集計")
+    )
+)]
+pub struct ApiDoc;
+
+/// ニュース
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct News {
+    /// ニュースの一意識別子
+    pub id: String,
+    /// タイトル（英語の原文、HTMLタグは除く）
+    pub title: String,
+    /// 説明文（英語の原文、HTMLタグは除く）
+    pub description: Option<String>,
+    /// 日本語タイトル（未翻訳なら`null`）
+    pub title_ja: Option<String>,
+    /// 日本語説明文
+    pub description_ja: Option<String>,
+    pub link: String,
+    /// ニュースソース（ESPN、RealGMなど）
+    pub source: String,
+    /// カテゴリー（Trade、Signing、Other）
+    pub category: String,
+    pub published_at: DateTime<Utc>,
+    /// 翻訳ステータス（pending、completed、failed）
+    pub translation_status: String,
+    pub translated_at: Option<DateTime<Utc>>,
+    /// 記事画像（画像プロキシ経由のURL）
+    pub image_url: Option<String>,
+    /// サムネイル画像（画像プロキシ経由のURL）
+    pub thumbnail_url: Option<String>,
+    /// 記事に名前が出てくるチームのID（ID順）
+    pub team_ids: Vec<String>,
+}
+
+impl News {
+    fn new(item: models::TradeNews, team_ids: Vec<String>) -> Self {
+        // HTMLタグの除去と画像URLの変換はGraphQLと同じ
+        let news = graphql::TradeNews::from(item);
+        News {
+            id: news.id,
+            title: news.title,
+            description: news.description,
+            title_ja: news.title_ja,
+            description_ja: news.description_ja,
+            link: news.link,
+            source: news.source,
+            category: news.category,
+            published_at: news.published_at,
+            translation_status: news.translation_status,
+            translated_at: news.translated_at,
+            image_url: news.image_url,
+            thumbnail_url: news.thumbnail_url,
+            team_ids,
+        }
+    }
+}
+
+/// ニュース一覧の1ページ
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsList {
+    /// 新しい順（公開日時・IDの降順）
+    pub items: Vec<News>,
+    /// 絞り込み条件に合う記事の総数（ページングとは無関係）
+    pub total_count: i64,
+    pub page_info: PageInfo,
+}
+
+/// ページングの情報（GraphQLの`PageInfo`と同じ）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+    /// ページの最初の記事のカーソル（`before`に渡すと前のページ）
+    pub start_cursor: Option<String>,
+    /// ページの最後の記事のカーソル（`after`に渡すと次のページ）
+    pub end_cursor: Option<String>,
+}
+
+/// ニュース一覧の絞り込み条件とページング
+///
+/// 条件はすべて満たす必要があります（AND）。
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct NewsQuery {
+    /// カテゴリー（Trade、Signing、Other）
+    pub category: Option<String>,
+    /// ニュースソース（大文字小文字は区別しない）
+    pub source: Option<String>,
+    /// 関連チームのID（例: LAL）
+    pub team: Option<String>,
+    /// この日時以降に公開された記事（RFC 3339、例: 2025-02-01T00:00:00Z）
+    pub published_after: Option<DateTime<Utc>>,
+    /// この日時以前に公開された記事（RFC 3339）
+    pub published_before: Option<DateTime<Utc>>,
+    /// 翻訳ステータス（pending、completed、failed）
+    pub translation_status: Option<String>,
+    /// 公式発表かどうか
+    pub is_official: Option<bool>,
+    /// 先頭から取得する件数（既定20件、最大100件）
+    #[param(minimum = 0, maximum = 100)]
+    pub first: Option<usize>,
+    /// このカーソルより後ろ（古い側）の記事
+    pub after: Option<String>,
+    /// 末尾から取得する件数（`first`とは同時に指定できない）
+    #[param(minimum = 0, maximum = 100)]
+    pub last: Option<usize>,
+    /// このカーソルより前（新しい側）の記事
+    pub before: Option<String>,
+}
+
+impl NewsQuery {
+    fn filter(&self) -> NewsFilter {
+        NewsFilter {
+            category: self.category.clone(),
+            source: self.source.clone(),
+            team_id: self.team.clone(),
+            published_after: self.published_after,
+            published_before: self.published_before,
+            translation_status: self.translation_status.clone(),
+            is_official: self.is_official,
+        }
+    }
+}
+
+/// カンファレンス
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Conference {
+    East,
+    West,
+}
+
+impl Conference {
+    const ALL: [Conference; 2] = [Conference::East, Conference::West];
+
+    /// データベース上の表記
+    fn db_name(self) -> &'static str {
+        match self {
+            Conference::East => "Eastern",
+            Conference::West => "Western",
+        }
+    }
+}
+
+/// ディビジョン
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Division {
+    Atlantic,
+    Central,
+    Southeast,
+    Northwest,
+    Pacific,
+    Southwest,
+}
+
+impl Division {
+    const ALL: [Division; 6] = [
+        Division::Atlantic,
+        Division::Central,
+        Division::Southeast,
+        Division::Northwest,
+        Division::Pacific,
+        Division::Southwest,
+    ];
+
+    /// データベース上の表記
+    fn db_name(self) -> &'static str {
+        match self {
+            Division::Atlantic => "Atlantic",
+            Division::Central => "Central",
+            Division::Southeast => "Southeast",
+            Division::Northwest => "Northwest",
+            Division::Pacific => "Pacific",
+            Division::Southwest => "Southwest",
+        }
+    }
+}
+
+/// NBAのチーム
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    /// チームID（略称と同じ。例: LAL）
+    pub id: String,
+    /// チーム名（例: Lakers）
+    pub name: String,
+    /// 日本語のチーム名
+    pub name_ja: Option<String>,
+    /// 本拠地（例: Los Angeles）
+    pub city: String,
+    /// 本拠地とチーム名（例: Los Angeles Lakers）
+    pub full_name: String,
+    pub conference: Option<Conference>,
+    pub division: Option<Division>,
+}
+
+impl From<models::Team> for Team {
+    fn from(team: models::Team) -> Self {
+        Team {
+            full_name: format!("{} {}", team.city, team.name),
+            conference: Conference::ALL
+                .into_iter()
+                .find(|conference| conference.db_name() == team.conference),
+            division: Division::ALL
+                .into_iter()
+                .find(|division| division.db_name() == team.division),
+            id: team.id,
+            name: team.name,
+            name_ja: team.name_ja,
+            city: team.city,
+        }
+    }
+}
+
+/// チーム一覧の絞り込み条件
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TeamsQuery {
+    pub conference: Option<Conference>,
+    pub division: Option<Division>,
+}
+
+/// 集計の区切りの単位
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsGranularity {
+    Hour,
+    #[default]
+    Day,
+    /// 月曜始まりの週
+    Week,
+}
+
+impl From<StatsGranularity> for stats::StatsGranularity {
+    fn from(granularity: StatsGranularity) -> Self {
+        match granularity {
+            StatsGranularity::Hour => stats::StatsGranularity::Hour,
+            StatsGranularity::Day => stats::StatsGranularity::Day,
+            StatsGranularity::Week => stats::StatsGranularity::Week,
+        }
+    }
+}
+
+/// 件数を分ける観点
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsGroupBy {
+    Category,
+    Source,
+    /// 関連チームのID（1件の記事が複数のチームに数えられることがある）
+    Team,
+    TranslationStatus,
+}
+
+impl From<StatsGroupBy> for stats::StatsGroupBy {
+    fn from(group_by: StatsGroupBy) -> Self {
+        match group_by {
+            StatsGroupBy::Category => stats::StatsGroupBy::Category,
+            StatsGroupBy::Source => stats::StatsGroupBy::Source,
+            StatsGroupBy::Team => stats::StatsGroupBy::Team,
+            StatsGroupBy::TranslationStatus => stats::StatsGroupBy::TranslationStatus,
+        }
+    }
+}
+
+/// 集計の条件
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// この日時以降に公開された記事（RFC 3339、含む）
+    pub from: DateTime<Utc>,
+    /// この日時より前に公開された記事（RFC 3339、含まない）
+    pub to: DateTime<Utc>,
+    /// 区切りの単位（既定は`day`）
+    #[serde(default)]
+    pub granularity: StatsGranularity,
+    /// 指定しなければ区切りごとの件数だけを返す
+    pub group_by: Option<StatsGroupBy>,
+    /// 区切りの基準にするIANAのタイムゾーン名（既定は`Asia/Tokyo`）
+    pub timezone: Option<String>,
+}
+
+/// 1つの区切りの件数
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsBucket {
+    /// 区切りの開始日時
+    pub start: DateTime<Utc>,
+    /// 区切りに含まれる記事の件数
+    pub count: i64,
+    /// `groupBy`ごとの件数（多い順、`groupBy`を省略すると空）
+    pub groups: Vec<StatsGroup>,
+}
+
+/// 区切りの中のグループごとの件数
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatsGroup {
+    pub key: String,
+    pub count: i64,
+}
+
+impl From<stats::StatsBucket> for StatsBucket {
+    fn from(bucket: stats::StatsBucket) -> Self {
+        StatsBucket {
+            start: bucket.start,
+            count: bucket.count,
+            groups: bucket
+                .groups
+                .into_iter()
+                .map(|group| StatsGroup {
+                    key: group.key,
+                    count: group.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// エラーのレスポンス（GraphQLのエラーと同じ形式）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub errors: Vec<ErrorDetail>,
+}
+
+/// エラーの内容
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorDetail {
+    pub message: String,
+    pub extensions: ErrorDetailExtensions,
+}
+
+/// エラーの種類
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorDetailExtensions {
+    /// エラーコード（例: NOT_FOUND、INVALID_INPUT）
+    pub code: String,
+}
+
+/// ハンドラーのエラー
+///
+/// `anyhow`のエラーは原因をたどってコードを決めます（[`errors::error_code`]）。
+pub struct ApiError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        let code = errors::error_code(&error);
+        let message = if code == ErrorCode::Internal {
+            error!("REST API request failed: {:#}", error);
+            "Internal server error".to_string()
+        } else {
+            error.to_string()
+        };
+        ApiError { code, message }
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        anyhow::Error::from(error).into()
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::InvalidInput(rejection.body_text()).into()
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            errors: vec![ErrorDetail {
+                message: self.message,
+                extensions: ErrorDetailExtensions {
+                    code: self.code.as_str().to_string(),
+                },
+            }],
+        };
+        (self.code.http_status(), Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// GraphQLの`news`接続と同じ形式のカーソル
+fn decode_cursor(cursor: Option<&str>) -> Result<Option<NewsCursor>, ApiError> {
+    cursor
+        .map(|cursor| {
+            OpaqueCursor::<NewsCursor>::decode_cursor(cursor)
+                .map(|cursor| cursor.0)
+                .map_err(|_| AppError::InvalidInput(format!("Invalid cursor: {}", cursor)).into())
+        })
+        .transpose()
+}
+
+fn encode_cursor(news: &models::TradeNews) -> String {
+    OpaqueCursor(NewsCursor::of(news)).encode_cursor()
+}
+
+/// 記事ごとの関連チームのID
+async fn team_ids_for(
+    repository: &SharedNewsRepository,
+    news: &[models::TradeNews],
+) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let ids: Vec<String> = news.iter().map(|item| item.id.clone()).collect();
+    let teams = repository.get_teams_for_news(&ids).await?;
+
+    Ok(teams
+        .into_iter()
+        .map(|(id, teams)| (id, teams.into_iter().map(|team| team.id).collect()))
+        .collect())
+}
+
+/// ニュース一覧（新しい順）
+///
+/// `first`・`last`は最大100件で、どちらも省略すると先頭20件を返します。
+#[utoipa::path(
+    get,
+    path = "/api/v1/news",
+    tag = "news",
+    params(NewsQuery),
+    responses(
+        (status = 200, description = "ニュース一覧の1ページ", body = NewsList),
+        (status = 400, description = "条件・カーソルが正しくない", body = ErrorResponse),
+        (status = 429, description = "レート制限", body = ErrorResponse)
+    )
+)]
+pub async fn list_news(
+    State(repository): State<SharedNewsRepository>,
+    query: Result<Query<NewsQuery>, QueryRejection>,
+) -> ApiResult<NewsList> {
+    let Query(query) = query?;
+    let filter = query.filter();
+
+    let page = repository::fetch_news_page(
+        repository.as_ref(),
+        &filter,
+        decode_cursor(query.after.as_deref())?,
+        decode_cursor(query.before.as_deref())?,
+        query.first,
+        query.last,
+    )
+    .await?;
+    let total_count = repository.count_matching_news(&filter).await?;
+    let mut team_ids = team_ids_for(&repository, &page.items).await?;
+
+    let page_info = PageInfo {
+        has_previous_page: page.has_previous_page,
+        has_next_page: page.has_next_page,
+        start_cursor: page.items.first().map(encode_cursor),
+        end_cursor: page.items.last().map(encode_cursor),
+    };
+    let items = page
+        .items
+        .into_iter()
+        .map(|item| {
+            let teams = team_ids.remove(&item.id).unwrap_or_default();
+            News::new(item, teams)
+        })
+        .collect();
+
+    Ok(Json(NewsList {
+        items,
+        total_count,
+        page_info,
+    }))
+}
+
+/// IDでニュースを取得
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/{id}",
+    tag = "news",
+    params(("id" = String, Path, description = "ニュースのID")),
+    responses(
+        (status = 200, description = "ニュース", body = News),
+        (status = 404, description = "ニュースがない", body = ErrorResponse)
+    )
+)]
+pub async fn get_news(
+    State(repository): State<SharedNewsRepository>,
+    Path(id): Path<String>,
+) -> ApiResult<News> {
+    let item = repository
+        .get_news(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("News not found: {}", id)))?;
+    let mut team_ids = team_ids_for(&repository, std::slice::from_ref(&item)).await?;
+
+    let teams = team_ids.remove(&item.id).unwrap_or_default();
+    Ok(Json(News::new(item, teams)))
+}
+
+/// チーム一覧（略称順）
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams",
+    tag = "teams",
+    params(TeamsQuery),
+    responses(
+        (status = 200, description = "チーム一覧", body = Vec<Team>),
+        (status = 400, description = "条件が正しくない", body = ErrorResponse)
+    )
+)]
+pub async fn list_teams(
+    State(repository): State<SharedNewsRepository>,
+    query: Result<Query<TeamsQuery>, QueryRejection>,
+) -> ApiResult<Vec<Team>> {
+    let Query(query) = query?;
+
+    let teams = repository.get_teams().await?;
+
+    Ok(Json(
+        teams
+            .into_iter()
+            .map(Team::from)
+            .filter(|team| {
+                query
+                    .conference
+                    .map_or(true, |c| team.conference == Some(c))
+            })
+            .filter(|team| query.division.map_or(true, |d| team.division == Some(d)))
+            .collect(),
+    ))
+}
+
+/// `from`から`to`（含まない）までに公開された記事の件数を区切りごとに返す
+///
+/// 区切りは`timezone`の暦に従い、件数のない区切りも含めて古い順に並びます。
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats",
+    tag = "stats",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "区切りごとの件数（古い順）", body = Vec<StatsBucket>),
+        (status = 400, description = "期間・タイムゾーンが正しくない", body = ErrorResponse)
+    )
+)]
+pub async fn news_stats(
+    State(repository): State<SharedNewsRepository>,
+    query: Result<Query<StatsQuery>, QueryRejection>,
+) -> ApiResult<Vec<StatsBucket>> {
+    let Query(query) = query?;
+
+    let timezone = query.timezone.as_deref().unwrap_or("Asia/Tokyo");
+    let timezone = timezone
+        .parse()
+        .map_err(|_| AppError::InvalidInput(format!("Unknown time zone: {}", timezone)))?;
+    let query = stats::StatsQuery {
+        from: query.from,
+        to: query.to,
+        granularity: query.granularity.into(),
+        group_by: query.group_by.map(Into::into),
+        timezone,
+    };
+    query.validate().map_err(AppError::invalid_input)?;
+    let buckets = repository.news_stats(&query).await?;
+
+    Ok(Json(buckets.into_iter().map(StatsBucket::from).collect()))
+}
+
+/// OpenAPI 3ドキュメント
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::news_item;
+    use crate::db::{InMemoryNewsStore, NewsOverride, NewsRepository};
+    use crate::scraper::NewsItem;
+    use axum::http::StatusCode;
+    use chrono::Duration;
+    use std::sync::Arc;
+    use tower::util::ServiceExt;
+
+    /// `hours_ago`時間前に公開されたニュース
+    fn published(id: &str, title: &str, hours_ago: i64) -> NewsItem {
+        NewsItem {
+            description: Some("<p>Test description</p>".to_string()),
+            published_at: Utc::now() - Duration::hours(hours_ago),
+            ..news_item(id, title)
+        }
+    }
+
+    async fn test_routes() -> Router {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![
+                published("rest-1", "Lakers acquire guard", 1),
+                published("rest-2", "Celtics sign forward", 2),
+                published("rest-3", "Lakers trade center", 3),
+                published("rest-4", "Warriors trade pick", 4),
+            ])
+            .await
+            .unwrap();
+        routes(store)
+    }
+
+    async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(uri)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn ids(json: &serde_json::Value) -> Vec<&str> {
+        json["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_news_pages_with_filters() {
+        let app = test_routes().await;
+
+        let (status, json) = get_json(&app, "/news?category=Trade&first=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&json), vec!["rest-1", "rest-3"]);
+        assert_eq!(json["totalCount"], 3);
+        assert_eq!(json["pageInfo"]["hasNextPage"], true);
+        assert_eq!(json["items"][0]["description"], "Test description");
+        assert_eq!(json["items"][0]["teamIds"], serde_json::json!(["LAL"]));
+
+        let cursor = json["pageInfo"]["endCursor"].as_str().unwrap();
+        let (_, json) = get_json(&app, &format!("/news?category=Trade&after={}", cursor)).await;
+        assert_eq!(ids(&json), vec!["rest-4"]);
+        assert_eq!(json["pageInfo"]["hasPreviousPage"], true);
+        assert_eq!(json["pageInfo"]["hasNextPage"], false);
+
+        let (_, json) = get_json(&app, "/news?team=LAL&last=1").await;
+        assert_eq!(ids(&json), vec!["rest-3"]);
+        assert_eq!(json["pageInfo"]["hasPreviousPage"], true);
+    }
+
+    #[tokio::test]
+    async fn test_cursors_are_shared_with_graphql() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![
+                published("shared-1", "Lakers acquire guard", 1),
+                published("shared-2", "Celtics sign forward", 2),
+            ])
+            .await
+            .unwrap();
+        let schema =
+            graphql::create_schema(store.clone(), Arc::new(crate::db::InMemoryAuthStore::new()));
+        let result = schema
+            .execute("{ news(first: 1) { pageInfo { endCursor } } }")
+            .await;
+        let json = result.data.into_json().unwrap();
+        let cursor = json["news"]["pageInfo"]["endCursor"].as_str().unwrap();
+
+        let (_, json) = get_json(&routes(store), &format!("/news?after={}", cursor)).await;
+        assert_eq!(ids(&json), vec!["shared-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_errors_have_codes_and_statuses() {
+        let app = test_routes().await;
+
+        let (status, json) = get_json(&app, "/news/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json["errors"][0]["extensions"]["code"], "NOT_FOUND");
+
+        for uri in [
+            "/news?first=1&last=1",
+            "/news?after=not-a-cursor",
+            "/news?isOfficial=maybe",
+            "/stats?from=2025-01-02T00:00:00Z&to=2025-01-01T00:00:00Z",
+            "/stats?from=2025-01-01T00:00:00Z&to=2025-01-02T00:00:00Z&timezone=Mars/Base",
+        ] {
+            let (status, json) = get_json(&app, uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+            assert_eq!(json["errors"][0]["extensions"]["code"], "INVALID_INPUT");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hidden_news_is_not_found() {
+        let store = Arc::new(InMemoryNewsStore::new());
+        store
+            .save_news_items(vec![published("hidden-1", "Lakers acquire guard", 1)])
+            .await
+            .unwrap();
+        let hide = NewsOverride {
+            hidden: Some(true),
+            ..NewsOverride::default()
+        };
+        store
+            .override_news("hidden-1", &hide, "jwt:editor")
+            .await
+            .unwrap();
+
+        let (status, json) = get_json(&routes(store), "/news/hidden-1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json["errors"][0]["extensions"]["code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_get_news_teams_and_stats() {
+        let app = test_routes().await;
+
+        let (status, json) = get_json(&app, "/news/rest-2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["title"], "Celtics sign forward");
+        assert_eq!(json["teamIds"], serde_json::json!(["BOS"]));
+
+        let (_, json) = get_json(&app, "/teams?conference=west&division=pacific").await;
+        let teams = json.as_array().unwrap();
+        assert_eq!(teams.len(), 5);
+        let lakers = teams.iter().find(|team| team["id"] == "LAL").unwrap();
+        assert_eq!(lakers["fullName"], "Los Angeles Lakers");
+        assert_eq!(lakers["conference"], "west");
+
+        let from =
+            (Utc::now() - Duration::days(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let to =
+            (Utc::now() + Duration::hours(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let (status, json) = get_json(
+            &app,
+            &format!(
+                "/stats?from={}&to={}&granularity=week&groupBy=category&timezone=UTC",
+                from, to
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let total: i64 = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bucket| bucket["count"].as_i64().unwrap())
+            .sum();
+        assert_eq!(total, 4);
+    }
+
+    #[tokio::test]
+    async fn test_openapi_document() {
+        let app = test_routes().await;
+
+        let (status, json) = get_json(&app, "/openapi.json").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["openapi"].as_str().unwrap().starts_with("3."));
+        for path in [
+            "/api/v1/news",
+            "/api/v1/news/{id}",
+            "/api/v1/teams",
+            "/api/v1/stats",
+        ] {
+            assert!(json["paths"][path]["get"].is_object(), "{}", path);
+        }
+        let params: Vec<&str> = json["paths"]["/api/v1/news"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["name"].as_str().unwrap())
+            .collect();
+        assert!(params.contains(&"publishedAfter"));
+        assert!(params.contains(&"after"));
+        assert!(json["components"]["schemas"]["NewsList"].is_object());
+        assert!(json["components"]["schemas"]["ErrorResponse"].is_object());
+    }
+}
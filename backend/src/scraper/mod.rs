@@ -4,9 +4,9 @@
 //! トレード関連の情報を抽出する機能を提供します。
 
 pub mod models;
-pub mod persistence;
+pub mod players;
 pub mod rss_parser;
+pub mod teams;
 
 pub use models::*;
-pub use persistence::*;
 pub use rss_parser::*;
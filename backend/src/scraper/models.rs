@@ -10,6 +10,8 @@ pub struct NewsItem {
     pub source: NewsSource,
     pub category: String, // "Trade", "Signing", "Other"
     pub published_at: DateTime<Utc>,
+    pub image_url: Option<String>, // enclosure / media:content の画像URL
+    pub thumbnail_url: Option<String>, // media:thumbnail の画像URL
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -130,6 +132,19 @@ impl NewsItem {
             format!("link-{:x}", hasher.finish())
         }
     }
+
+    /// 記事内容（タイトルと説明文）のハッシュを計算する
+    ///
+    /// 再スクレイピング時に見出しや本文が更新されたかどうかの判定に使います。
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.title.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.description.as_deref().unwrap_or("").as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
 }
 
 #[cfg(test)]
@@ -203,4 +218,38 @@ mod tests {
         assert!(id.starts_with("link-"));
         assert!(id.len() > 5);
     }
+
+    #[test]
+    fn test_content_hash_changes_with_content() {
+        let item = NewsItem {
+            id: "hash-1".to_string(),
+            title: "Lakers nearing deal".to_string(),
+            description: Some("Talks are ongoing".to_string()),
+            link: "https://example.com/news/1".to_string(),
+            source: NewsSource::ESPN,
+            category: "Trade".to_string(),
+            published_at: Utc::now(),
+            image_url: None,
+            thumbnail_url: None,
+        };
+
+        let same = NewsItem {
+            published_at: Utc::now(),
+            ..item.clone()
+        };
+        assert_eq!(item.content_hash(), same.content_hash());
+        assert_eq!(item.content_hash().len(), 64);
+
+        let retitled = NewsItem {
+            title: "Lakers acquire X".to_string(),
+            ..item.clone()
+        };
+        assert_ne!(item.content_hash(), retitled.content_hash());
+
+        let without_description = NewsItem {
+            description: None,
+            ..item.clone()
+        };
+        assert_ne!(item.content_hash(), without_description.content_hash());
+    }
 }
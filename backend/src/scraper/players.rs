@@ -0,0 +1,131 @@
+//! 選手データと記事からの選手判定
+//!
+//! 記事のタイトル・説明文に選手のフルネーム（例: LeBron James）が出てくれば
+//! その選手の記事とみなします。姓だけの言及は同姓の選手と区別できないため数えません。
+
+use crate::scraper::teams::contains_word;
+use crate::utils::string_utils::strip_html_tags;
+
+/// マイグレーションで投入される選手と同じデータ（ID、名前、日本語名）
+pub const NBA_PLAYERS: &[(&str, &str, &str)] = &[
+    ("anthony-davis", "Anthony Davis", "アンソニー・デイビス"),
+    (
+        "anthony-edwards",
+        "Anthony Edwards",
+        "アンソニー・エドワーズ",
+    ),
+    ("bam-adebayo", "Bam Adebayo", "バム・アデバヨ"),
+    ("damian-lillard", "Damian Lillard", "デイミアン・リラード"),
+    ("deaaron-fox", "De'Aaron Fox", "ディアロン・フォックス"),
+    ("devin-booker", "Devin Booker", "デビン・ブッカー"),
+    (
+        "donovan-mitchell",
+        "Donovan Mitchell",
+        "ドノバン・ミッチェル",
+    ),
+    (
+        "giannis-antetokounmpo",
+        "Giannis Antetokounmpo",
+        "ヤニス・アデトクンボ",
+    ),
+    ("ja-morant", "Ja Morant", "ジャ・モラント"),
+    ("jalen-brunson", "Jalen Brunson", "ジェイレン・ブランソン"),
+    ("james-harden", "James Harden", "ジェームズ・ハーデン"),
+    ("jayson-tatum", "Jayson Tatum", "ジェイソン・テイタム"),
+    ("jimmy-butler", "Jimmy Butler", "ジミー・バトラー"),
+    ("joel-embiid", "Joel Embiid", "ジョエル・エンビード"),
+    (
+        "karl-anthony-towns",
+        "Karl-Anthony Towns",
+        "カール・アンソニー・タウンズ",
+    ),
+    ("kawhi-leonard", "Kawhi Leonard", "カワイ・レナード"),
+    ("kevin-durant", "Kevin Durant", "ケビン・デュラント"),
+    ("kyrie-irving", "Kyrie Irving", "カイリー・アービング"),
+    ("lauri-markkanen", "Lauri Markkanen", "ラウリ・マルカネン"),
+    ("lebron-james", "LeBron James", "レブロン・ジェームズ"),
+    ("luka-doncic", "Luka Doncic", "ルカ・ドンチッチ"),
+    ("nikola-jokic", "Nikola Jokic", "ニコラ・ヨキッチ"),
+    ("paul-george", "Paul George", "ポール・ジョージ"),
+    ("rui-hachimura", "Rui Hachimura", "八村塁"),
+    (
+        "shai-gilgeous-alexander",
+        "Shai Gilgeous-Alexander",
+        "シェイ・ギルジャス・アレクサンダー",
+    ),
+    ("stephen-curry", "Stephen Curry", "ステフィン・カリー"),
+    ("trae-young", "Trae Young", "トレイ・ヤング"),
+    (
+        "tyrese-haliburton",
+        "Tyrese Haliburton",
+        "タイリース・ハリバートン",
+    ),
+    (
+        "victor-wembanyama",
+        "Victor Wembanyama",
+        "ビクター・ウェンバンヤマ",
+    ),
+    ("yuki-kawamura", "Yuki Kawamura", "河村勇輝"),
+    ("zach-lavine", "Zach LaVine", "ザック・ラビーン"),
+    (
+        "zion-williamson",
+        "Zion Williamson",
+        "ザイオン・ウィリアムソン",
+    ),
+];
+
+/// 名前以外の表記（選手ID、表記）
+///
+/// マイグレーション`add_players_and_trending`のバックフィルと揃えること。
+const PLAYER_ALIASES: &[(&str, &str)] = &[
+    ("luka-doncic", "Luka Dončić"),
+    ("nikola-jokic", "Nikola Jokić"),
+    ("shai-gilgeous-alexander", "SGA"),
+];
+
+/// 記事のタイトル・説明文に出てくる選手のIDを返す（ID順、重複なし）
+pub fn detect_player_ids(title: &str, description: Option<&str>) -> Vec<String> {
+    let text = format!(
+        "{} {}",
+        strip_html_tags(title),
+        strip_html_tags(description.unwrap_or(""))
+    );
+
+    let names = NBA_PLAYERS
+        .iter()
+        .map(|(id, name, _)| (*id, *name))
+        .chain(PLAYER_ALIASES.iter().copied());
+
+    let mut ids: Vec<String> = names
+        .filter(|(_, name)| contains_word(&text, name))
+        .map(|(id, _)| id.to_string())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_player_ids_matches_full_names() {
+        let ids = detect_player_ids(
+            "Mavericks trade Luka Doncic to Lakers",
+            Some("<p>Doncic joins LeBron James and Anthony Davis.</p>"),
+        );
+        assert_eq!(ids, vec!["anthony-davis", "lebron-james", "luka-doncic"]);
+
+        // 姓だけ、名前の一部だけの言及は数えない
+        assert!(detect_player_ids("James sits out; Jamesons win", None).is_empty());
+    }
+
+    #[test]
+    fn test_detect_player_ids_uses_aliases() {
+        assert_eq!(
+            detect_player_ids("Nikola Jokić and SGA headline MVP race", None),
+            vec!["nikola-jokic", "shai-gilgeous-alexander"]
+        );
+    }
+}
@@ -1,9 +1,13 @@
-use anyhow::{Context, Result};
+use std::time::Instant;
+
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rss::Channel;
 use tracing::{debug, error, info};
 
+use crate::errors::AppError;
+use crate::metrics::metrics;
 use crate::scraper::models::{NewsItem, NewsSource, RssFeed, RSS_FEEDS};
 
 pub struct RssParser {
@@ -41,11 +45,19 @@ impl RssParser {
             }
         }
 
-        all_news.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        all_news.sort_by_key(|item| std::cmp::Reverse(item.published_at));
         Ok(all_news)
     }
 
+    /// フィードを取得して記事を取り出す（件数・所要時間・失敗はメトリクスに記録）
     pub async fn fetch_feed(&self, feed: &RssFeed) -> Result<Vec<NewsItem>> {
+        let started = Instant::now();
+        let result = self.fetch_feed_items(feed).await;
+        metrics().record_fetch(&feed.source.to_string(), started.elapsed(), &result);
+        result
+    }
+
+    async fn fetch_feed_items(&self, feed: &RssFeed) -> Result<Vec<NewsItem>> {
         info!("Fetching RSS feed from: {}", feed.url);
 
         let response = self
@@ -53,14 +65,15 @@ impl RssParser {
             .get(&feed.url)
             .send()
             .await
-            .context("Failed to fetch RSS feed")?;
+            .map_err(|e| AppError::FeedFetch(e.to_string()))?;
 
         let content = response
             .text()
             .await
-            .context("Failed to read response body")?;
+            .map_err(|e| AppError::FeedFetch(format!("failed to read response body - {e}")))?;
 
-        let channel = Channel::read_from(content.as_bytes()).context("Failed to parse RSS feed")?;
+        let channel = Channel::read_from(content.as_bytes())
+            .map_err(|e| AppError::FeedParse(e.to_string()))?;
 
         let mut news_items = Vec::new();
 
@@ -92,6 +105,9 @@ impl RssParser {
         // カテゴリーを判定
         let category = NewsItem::determine_category(&title, description.as_deref());
 
+        // 画像URLを抽出
+        let (image_url, thumbnail_url) = extract_media_urls(item);
+
         Some(NewsItem {
             id,
             title,
@@ -100,10 +116,113 @@ impl RssParser {
             published_at,
             source: source.clone(),
             category,
+            image_url,
+            thumbnail_url,
         })
     }
 }
 
+/// RSSアイテムから画像URLとサムネイルURLを抽出する
+///
+/// 画像は `media:content`（幅が最大のもの）、`enclosure` の順に、
+/// サムネイルは `media:thumbnail` を優先して探します。
+/// どちらか一方しか見つからない場合は、もう一方で補完します。
+fn extract_media_urls(item: &rss::Item) -> (Option<String>, Option<String>) {
+    let mut contents = Vec::new();
+    let mut thumbnails = Vec::new();
+
+    if let Some(media) = item.extensions().get("media") {
+        for (name, extensions) in media {
+            for extension in extensions {
+                collect_media_extension(name, extension, &mut contents, &mut thumbnails);
+            }
+        }
+    }
+
+    let media_image = contents
+        .iter()
+        .filter(|ext| is_image_media(ext))
+        .max_by_key(|ext| media_width(ext))
+        .and_then(|ext| ext.attrs().get("url").cloned());
+
+    let enclosure_image = item
+        .enclosure()
+        .filter(|enclosure| enclosure.mime_type().starts_with("image/"))
+        .map(|enclosure| enclosure.url().to_string());
+
+    let thumbnail = thumbnails
+        .iter()
+        .max_by_key(|ext| media_width(ext))
+        .and_then(|ext| ext.attrs().get("url").cloned());
+
+    let image_url = media_image
+        .or(enclosure_image)
+        .filter(|url| !url.trim().is_empty());
+    let thumbnail_url = thumbnail.filter(|url| !url.trim().is_empty());
+
+    match (image_url, thumbnail_url) {
+        (Some(image), None) => (Some(image.clone()), Some(image)),
+        (None, Some(thumbnail)) => (Some(thumbnail.clone()), Some(thumbnail)),
+        pair => pair,
+    }
+}
+
+/// `media:group` を展開しつつ `media:content` と `media:thumbnail` を収集する
+fn collect_media_extension<'a>(
+    name: &str,
+    extension: &'a rss::extension::Extension,
+    contents: &mut Vec<&'a rss::extension::Extension>,
+    thumbnails: &mut Vec<&'a rss::extension::Extension>,
+) {
+    match name {
+        "content" => {
+            contents.push(extension);
+            // media:contentの子要素にmedia:thumbnailが含まれることがある
+            if let Some(children) = extension.children().get("thumbnail") {
+                thumbnails.extend(children.iter());
+            }
+        }
+        "thumbnail" => thumbnails.push(extension),
+        "group" => {
+            for (child_name, children) in extension.children() {
+                for child in children {
+                    collect_media_extension(child_name, child, contents, thumbnails);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `media:content` が画像を指しているか判定する
+fn is_image_media(extension: &rss::extension::Extension) -> bool {
+    let attrs = extension.attrs();
+    if !attrs.contains_key("url") {
+        return false;
+    }
+
+    match (attrs.get("medium"), attrs.get("type")) {
+        (Some(medium), _) => medium == "image",
+        (None, Some(mime_type)) => mime_type.starts_with("image/"),
+        // 種別が指定されていない場合は拡張子で判断する
+        (None, None) => attrs.get("url").is_some_and(|url| {
+            let path = url.split(['?', '#']).next().unwrap_or_default();
+            let path = path.to_lowercase();
+            [".jpg", ".jpeg", ".png", ".gif", ".webp"]
+                .iter()
+                .any(|ext| path.ends_with(ext))
+        }),
+    }
+}
+
+fn media_width(extension: &rss::extension::Extension) -> u32 {
+    extension
+        .attrs()
+        .get("width")
+        .and_then(|width| width.parse().ok())
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +340,134 @@ mod tests {
         assert!(news.id.starts_with("link-"));
     }
 
+    /// media名前空間付きのRSSから最初のアイテムを取り出す
+    fn parse_first_item(item_xml: &str) -> rss::Item {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+                <channel>
+                    <title>Test</title>
+                    <link>https://example.com</link>
+                    <description>Test feed</description>
+                    {item_xml}
+                </channel>
+            </rss>"#
+        );
+        let channel = Channel::read_from(xml.as_bytes()).unwrap();
+        channel.items()[0].clone()
+    }
+
+    #[test]
+    fn test_parse_rss_item_with_image_enclosure() {
+        let parser = RssParser::new();
+        let item = parse_first_item(
+            r#"<item>
+                <title>Lakers Trade News</title>
+                <link>https://example.com/news/1</link>
+                <enclosure url="https://cdn.example.com/photo.jpg" length="12345" type="image/jpeg"/>
+            </item>"#,
+        );
+
+        let news = parser.parse_rss_item(&item, &NewsSource::ESPN).unwrap();
+        assert_eq!(
+            news.image_url.as_deref(),
+            Some("https://cdn.example.com/photo.jpg")
+        );
+        // サムネイルがない場合は画像URLで補完される
+        assert_eq!(
+            news.thumbnail_url.as_deref(),
+            Some("https://cdn.example.com/photo.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_rss_item_ignores_non_image_enclosure() {
+        let parser = RssParser::new();
+        let item = parse_first_item(
+            r#"<item>
+                <title>Podcast Episode</title>
+                <link>https://example.com/news/2</link>
+                <enclosure url="https://cdn.example.com/episode.mp3" length="999" type="audio/mpeg"/>
+            </item>"#,
+        );
+
+        let news = parser.parse_rss_item(&item, &NewsSource::ESPN).unwrap();
+        assert_eq!(news.image_url, None);
+        assert_eq!(news.thumbnail_url, None);
+    }
+
+    #[test]
+    fn test_parse_rss_item_with_media_content_and_thumbnail() {
+        let parser = RssParser::new();
+        let item = parse_first_item(
+            r#"<item>
+                <title>Celtics Sign Guard</title>
+                <link>https://example.com/news/3</link>
+                <media:content url="https://cdn.example.com/small.jpg" medium="image" width="320"/>
+                <media:content url="https://cdn.example.com/large.jpg" medium="image" width="1280"/>
+                <media:content url="https://cdn.example.com/clip.mp4" medium="video" width="1920"/>
+                <media:thumbnail url="https://cdn.example.com/thumb.jpg" width="150"/>
+            </item>"#,
+        );
+
+        let news = parser.parse_rss_item(&item, &NewsSource::RealGM).unwrap();
+        assert_eq!(
+            news.image_url.as_deref(),
+            Some("https://cdn.example.com/large.jpg")
+        );
+        assert_eq!(
+            news.thumbnail_url.as_deref(),
+            Some("https://cdn.example.com/thumb.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_rss_item_with_media_group() {
+        let parser = RssParser::new();
+        let item = parse_first_item(
+            r#"<item>
+                <title>Warriors Update</title>
+                <link>https://example.com/news/4</link>
+                <media:group>
+                    <media:content url="https://cdn.example.com/group.png" type="image/png"/>
+                    <media:thumbnail url="https://cdn.example.com/group-thumb.png"/>
+                </media:group>
+            </item>"#,
+        );
+
+        let news = parser.parse_rss_item(&item, &NewsSource::ESPN).unwrap();
+        assert_eq!(
+            news.image_url.as_deref(),
+            Some("https://cdn.example.com/group.png")
+        );
+        assert_eq!(
+            news.thumbnail_url.as_deref(),
+            Some("https://cdn.example.com/group-thumb.png")
+        );
+    }
+
+    #[test]
+    fn test_parse_rss_item_with_only_thumbnail() {
+        let parser = RssParser::new();
+        let item = parse_first_item(
+            r#"<item>
+                <title>Heat Update</title>
+                <link>https://example.com/news/5</link>
+                <media:thumbnail url="https://cdn.example.com/only-thumb.jpg"/>
+            </item>"#,
+        );
+
+        let news = parser.parse_rss_item(&item, &NewsSource::ESPN).unwrap();
+        assert_eq!(
+            news.image_url.as_deref(),
+            Some("https://cdn.example.com/only-thumb.jpg")
+        );
+        assert_eq!(
+            news.thumbnail_url.as_deref(),
+            Some("https://cdn.example.com/only-thumb.jpg")
+        );
+    }
+
     #[test]
     fn test_parse_rss_item_with_invalid_date() {
         let parser = RssParser::new();
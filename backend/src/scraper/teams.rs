@@ -0,0 +1,324 @@
+//! チームデータと記事からのチーム判定
+//!
+//! 記事のタイトル・説明文にチームのニックネーム（例: Lakers）が出てくれば
+//! そのチームの記事とみなします。
+
+use crate::utils::string_utils::strip_html_tags;
+
+/// マイグレーションで投入されるチームと同じデータ
+/// （ID、チーム名、日本語名、都市、カンファレンス、ディビジョン）
+pub const NBA_TEAMS: &[(&str, &str, &str, &str, &str, &str)] = &[
+    (
+        "BOS",
+        "Celtics",
+        "ボストン・セルティックス",
+        "Boston",
+        "Eastern",
+        "Atlantic",
+    ),
+    (
+        "BKN",
+        "Nets",
+        "ブルックリン・ネッツ",
+        "Brooklyn",
+        "Eastern",
+        "Atlantic",
+    ),
+    (
+        "NYK",
+        "Knicks",
+        "ニューヨーク・ニックス",
+        "New York",
+        "Eastern",
+        "Atlantic",
+    ),
+    (
+        "PHI",
+        "76ers",
+        "フィラデルフィア・76ers",
+        "Philadelphia",
+        "Eastern",
+        "Atlantic",
+    ),
+    (
+        "TOR",
+        "Raptors",
+        "トロント・ラプターズ",
+        "Toronto",
+        "Eastern",
+        "Atlantic",
+    ),
+    (
+        "CHI",
+        "Bulls",
+        "シカゴ・ブルズ",
+        "Chicago",
+        "Eastern",
+        "Central",
+    ),
+    (
+        "CLE",
+        "Cavaliers",
+        "クリーブランド・キャバリアーズ",
+        "Cleveland",
+        "Eastern",
+        "Central",
+    ),
+    (
+        "DET",
+        "Pistons",
+        "デトロイト・ピストンズ",
+        "Detroit",
+        "Eastern",
+        "Central",
+    ),
+    (
+        "IND",
+        "Pacers",
+        "インディアナ・ペイサーズ",
+        "Indiana",
+        "Eastern",
+        "Central",
+    ),
+    (
+        "MIL",
+        "Bucks",
+        "ミルウォーキー・バックス",
+        "Milwaukee",
+        "Eastern",
+        "Central",
+    ),
+    (
+        "ATL",
+        "Hawks",
+        "アトランタ・ホークス",
+        "Atlanta",
+        "Eastern",
+        "Southeast",
+    ),
+    (
+        "CHA",
+        "Hornets",
+        "シャーロット・ホーネッツ",
+        "Charlotte",
+        "Eastern",
+        "Southeast",
+    ),
+    (
+        "MIA",
+        "Heat",
+        "マイアミ・ヒート",
+        "Miami",
+        "Eastern",
+        "Southeast",
+    ),
+    (
+        "ORL",
+        "Magic",
+        "オーランド・マジック",
+        "Orlando",
+        "Eastern",
+        "Southeast",
+    ),
+    (
+        "WAS",
+        "Wizards",
+        "ワシントン・ウィザーズ",
+        "Washington",
+        "Eastern",
+        "Southeast",
+    ),
+    (
+        "DEN",
+        "Nuggets",
+        "デンバー・ナゲッツ",
+        "Denver",
+        "Western",
+        "Northwest",
+    ),
+    (
+        "MIN",
+        "Timberwolves",
+        "ミネソタ・ティンバーウルブズ",
+        "Minnesota",
+        "Western",
+        "Northwest",
+    ),
+    (
+        "OKC",
+        "Thunder",
+        "オクラホマシティ・サンダー",
+        "Oklahoma City",
+        "Western",
+        "Northwest",
+    ),
+    (
+        "POR",
+        "Trail Blazers",
+        "ポートランド・トレイルブレイザーズ",
+        "Portland",
+        "Western",
+        "Northwest",
+    ),
+    (
+        "UTA",
+        "Jazz",
+        "ユタ・ジャズ",
+        "Utah",
+        "Western",
+        "Northwest",
+    ),
+    (
+        "GSW",
+        "Warriors",
+        "ゴールデンステート・ウォリアーズ",
+        "Golden State",
+        "Western",
+        "Pacific",
+    ),
+    (
+        "LAC",
+        "Clippers",
+        "ロサンゼルス・クリッパーズ",
+        "Los Angeles",
+        "Western",
+        "Pacific",
+    ),
+    (
+        "LAL",
+        "Lakers",
+        "ロサンゼルス・レイカーズ",
+        "Los Angeles",
+        "Western",
+        "Pacific",
+    ),
+    (
+        "PHX",
+        "Suns",
+        "フェニックス・サンズ",
+        "Phoenix",
+        "Western",
+        "Pacific",
+    ),
+    (
+        "SAC",
+        "Kings",
+        "サクラメント・キングス",
+        "Sacramento",
+        "Western",
+        "Pacific",
+    ),
+    (
+        "DAL",
+        "Mavericks",
+        "ダラス・マーベリックス",
+        "Dallas",
+        "Western",
+        "Southwest",
+    ),
+    (
+        "HOU",
+        "Rockets",
+        "ヒューストン・ロケッツ",
+        "Houston",
+        "Western",
+        "Southwest",
+    ),
+    (
+        "MEM",
+        "Grizzlies",
+        "メンフィス・グリズリーズ",
+        "Memphis",
+        "Western",
+        "Southwest",
+    ),
+    (
+        "NOP",
+        "Pelicans",
+        "ニューオーリンズ・ペリカンズ",
+        "New Orleans",
+        "Western",
+        "Southwest",
+    ),
+    (
+        "SAS",
+        "Spurs",
+        "サンアントニオ・スパーズ",
+        "San Antonio",
+        "Western",
+        "Southwest",
+    ),
+];
+
+/// ニックネーム以外によく使われる呼び名（チームID、呼び名）
+///
+/// マイグレーション`add_news_teams`のバックフィルと揃えること。
+const TEAM_ALIASES: &[(&str, &str)] = &[
+    ("PHI", "Sixers"),
+    ("POR", "Blazers"),
+    ("CLE", "Cavs"),
+    ("DAL", "Mavs"),
+];
+
+/// 記事のタイトル・説明文に出てくるチームのIDを返す（ID順、重複なし）
+///
+/// 一般的な単語と紛れないよう（例: Heat、Magic）、大文字小文字を区別して
+/// 単語単位で照合します。
+pub fn detect_team_ids(title: &str, description: Option<&str>) -> Vec<String> {
+    let text = format!(
+        "{} {}",
+        strip_html_tags(title),
+        strip_html_tags(description.unwrap_or(""))
+    );
+
+    let names = NBA_TEAMS
+        .iter()
+        .map(|(id, name, ..)| (*id, *name))
+        .chain(TEAM_ALIASES.iter().copied());
+
+    let mut ids: Vec<String> = names
+        .filter(|(_, name)| contains_word(&text, name))
+        .map(|(id, _)| id.to_string())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// 前後が英数字でない位置に`word`が現れるか
+pub(crate) fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_team_ids_finds_all_mentioned_teams() {
+        let ids = detect_team_ids(
+            "Mavericks trade Luka Doncic to Lakers",
+            Some("<p>The Jazz also join the three-team deal.</p>"),
+        );
+        assert_eq!(ids, vec!["DAL", "LAL", "UTA"]);
+    }
+
+    #[test]
+    fn test_detect_team_ids_matches_whole_words_only() {
+        // "heated"や小文字の"magic"はチームとみなさない
+        assert!(detect_team_ids("A heated debate about magic numbers", None).is_empty());
+        assert_eq!(detect_team_ids("Heat sign guard", None), vec!["MIA"]);
+    }
+
+    #[test]
+    fn test_detect_team_ids_uses_aliases_and_multiword_names() {
+        assert_eq!(
+            detect_team_ids("Sixers and Trail Blazers talk", Some("Blazers want picks")),
+            vec!["PHI", "POR"]
+        );
+    }
+}
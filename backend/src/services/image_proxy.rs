@@ -0,0 +1,715 @@
+//! 画像プロキシの実装
+//!
+//! 外部サイトのサムネイル画像を取得・縮小し、ローカルディスクにキャッシュします。
+//! フロントエンドは第三者のホストを直接参照せず、このプロキシ経由で画像を表示します。
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use reqwest::{redirect, Client, Response, Url};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::utils::env_utils::env_parse;
+
+/// プロキシ経由で画像を配信するパス
+pub const IMAGE_PROXY_PATH: &str = "/images/thumbnail";
+
+/// 生成するサムネイルの幅（キャッシュの種類を抑えるため固定値に丸める）
+pub const THUMBNAIL_WIDTHS: &[u32] = &[160, 320, 640];
+
+/// 幅が指定されなかった場合のサムネイル幅
+pub const DEFAULT_THUMBNAIL_WIDTH: u32 = 320;
+
+/// 画像の取得・変換に失敗した場合に返すプレースホルダー画像
+pub const PLACEHOLDER_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="320" height="180" viewBox="0 0 320 180"><rect width="320" height="180" fill="#e5e7eb"/><circle cx="160" cy="90" r="28" fill="none" stroke="#9ca3af" stroke-width="6"/><path d="M132 90h56M160 62c-12 16-12 40 0 56M160 62c12 16 12 40 0 56" fill="none" stroke="#9ca3af" stroke-width="4"/></svg>"##;
+
+const JPEG_QUALITY: u8 = 80;
+
+/// 元画像を取得するときにたどるリダイレクトの最大数
+const MAX_REDIRECTS: usize = 3;
+
+#[derive(Error, Debug)]
+pub enum ImageProxyError {
+    #[error("Invalid image URL: {0}")]
+    InvalidUrl(String),
+    #[error("Host is not allowed: {0}")]
+    ForbiddenHost(String),
+    #[error("Failed to fetch image: {0}")]
+    FetchFailed(String),
+    #[error("Image exceeds size limit of {0} bytes")]
+    TooLarge(usize),
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+    #[error("Failed to decode image: {0}")]
+    DecodeFailed(String),
+    #[error("Cache error: {0}")]
+    Cache(#[from] std::io::Error),
+}
+
+impl ImageProxyError {
+    /// クライアント側の指定ミスによるエラーかどうか
+    ///
+    /// それ以外（取得失敗・壊れた画像など）はプレースホルダー画像で代替します。
+    pub fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            ImageProxyError::InvalidUrl(_) | ImageProxyError::ForbiddenHost(_)
+        )
+    }
+}
+
+/// 画像プロキシの設定
+#[derive(Debug, Clone)]
+pub struct ImageProxyConfig {
+    /// キャッシュディレクトリ
+    pub cache_dir: PathBuf,
+    /// 取得する元画像の最大サイズ（バイト）
+    pub max_source_bytes: usize,
+    /// キャッシュディレクトリ全体の最大サイズ（バイト）
+    pub max_cache_bytes: u64,
+    /// 元画像取得のタイムアウト
+    pub fetch_timeout: Duration,
+}
+
+impl Default for ImageProxyConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: std::env::temp_dir().join("nba-trade-scraper-images"),
+            max_source_bytes: 5 * 1024 * 1024,
+            max_cache_bytes: 512 * 1024 * 1024,
+            fetch_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ImageProxyConfig {
+    /// 環境変数から設定を読み込む（未設定の項目はデフォルト値）
+    ///
+    /// - `IMAGE_CACHE_DIR`: キャッシュディレクトリ
+    /// - `IMAGE_MAX_SOURCE_BYTES`: 元画像の最大サイズ
+    /// - `IMAGE_MAX_CACHE_BYTES`: キャッシュ全体の最大サイズ
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            cache_dir: std::env::var("IMAGE_CACHE_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(defaults.cache_dir),
+            max_source_bytes: env_parse("IMAGE_MAX_SOURCE_BYTES")
+                .unwrap_or(defaults.max_source_bytes),
+            max_cache_bytes: env_parse("IMAGE_MAX_CACHE_BYTES").unwrap_or(defaults.max_cache_bytes),
+            fetch_timeout: defaults.fetch_timeout,
+        }
+    }
+}
+
+/// サムネイル画像のプロキシ
+pub struct ImageProxy {
+    config: ImageProxyConfig,
+    /// 接続してよいIPアドレスか
+    is_allowed_ip: fn(IpAddr) -> bool,
+}
+
+impl ImageProxy {
+    pub fn new(config: ImageProxyConfig) -> Self {
+        Self {
+            config,
+            is_allowed_ip: is_public_ip,
+        }
+    }
+
+    /// ループバックアドレスにも接続できるプロキシ（ローカルのモックサーバーを使うテスト用）
+    #[cfg(test)]
+    fn allowing_loopback(config: ImageProxyConfig) -> Self {
+        Self {
+            config,
+            is_allowed_ip: |ip| ip.is_loopback() || is_public_ip(ip),
+        }
+    }
+
+    pub fn config(&self) -> &ImageProxyConfig {
+        &self.config
+    }
+
+    /// 指定URLの画像を縮小したJPEGを返す（キャッシュがあればそれを使う）
+    pub async fn thumbnail(&self, url: &str, width: u32) -> Result<Vec<u8>, ImageProxyError> {
+        let url = validate_source_url(url)?;
+        let width = snap_width(width);
+        let cache_path = self.cache_path(url.as_str(), width);
+
+        if let Ok(bytes) = tokio::fs::read(&cache_path).await {
+            debug!("Image cache hit: {}", url);
+            return Ok(bytes);
+        }
+
+        let source = self.fetch_source(&url).await?;
+        let thumbnail = tokio::task::spawn_blocking(move || resize_to_jpeg(&source, width))
+            .await
+            .map_err(|e| ImageProxyError::DecodeFailed(e.to_string()))??;
+
+        self.store(&cache_path, &thumbnail).await?;
+
+        Ok(thumbnail)
+    }
+
+    /// 元画像をサイズ上限付きで取得する
+    ///
+    /// リダイレクトは自動ではたどらず、リダイレクト先にも同じ検証をしてから接続します。
+    async fn fetch_source(&self, url: &Url) -> Result<Vec<u8>, ImageProxyError> {
+        let mut url = url.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            let response = self.send(&url).await?;
+            if !response.status().is_redirection() {
+                return self.read_image(response).await;
+            }
+
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| {
+                    ImageProxyError::FetchFailed(format!(
+                        "HTTP {} without Location",
+                        response.status()
+                    ))
+                })?;
+            let next = url
+                .join(location)
+                .map_err(|e| ImageProxyError::InvalidUrl(e.to_string()))?;
+            debug!("Image redirected: {} -> {}", url, next);
+            url = validate_source_url(next.as_str())?;
+        }
+
+        Err(ImageProxyError::FetchFailed(format!(
+            "more than {MAX_REDIRECTS} redirects"
+        )))
+    }
+
+    /// 検証したアドレスに接続してリクエストを送る
+    ///
+    /// 名前解決した結果を接続先に固定し、検証後にDNSの応答が変わっても
+    /// （DNSリバインディング）内部のアドレスに接続しないようにします。
+    async fn send(&self, url: &Url) -> Result<Response, ImageProxyError> {
+        let address = self.resolve(url).await?;
+        let mut builder = Client::builder()
+            .timeout(self.config.fetch_timeout)
+            .redirect(redirect::Policy::none());
+        if let Some(url::Host::Domain(domain)) = url.host() {
+            builder = builder.resolve(domain, address);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| ImageProxyError::FetchFailed(e.to_string()))?;
+
+        client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| ImageProxyError::FetchFailed(e.to_string()))
+    }
+
+    /// ホストを名前解決し、接続してよいアドレスを返す
+    async fn resolve(&self, url: &Url) -> Result<SocketAddr, ImageProxyError> {
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addresses: Vec<SocketAddr> = match url.host() {
+            None => return Err(ImageProxyError::InvalidUrl("missing host".to_string())),
+            Some(url::Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+                .await
+                .map_err(|e| {
+                    ImageProxyError::FetchFailed(format!("failed to resolve {domain}: {e}"))
+                })?
+                .collect(),
+            Some(url::Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+            Some(url::Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        };
+
+        checked_address(
+            url.host_str().unwrap_or_default(),
+            &addresses,
+            self.is_allowed_ip,
+        )
+    }
+
+    /// レスポンスから画像を読み込む
+    async fn read_image(&self, mut response: Response) -> Result<Vec<u8>, ImageProxyError> {
+        if !response.status().is_success() {
+            return Err(ImageProxyError::FetchFailed(format!(
+                "HTTP {}",
+                response.status()
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !content_type.starts_with("image/") {
+            return Err(ImageProxyError::UnsupportedContentType(content_type));
+        }
+
+        let max = self.config.max_source_bytes;
+        if response
+            .content_length()
+            .is_some_and(|length| length as usize > max)
+        {
+            return Err(ImageProxyError::TooLarge(max));
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ImageProxyError::FetchFailed(e.to_string()))?
+        {
+            if bytes.len() + chunk.len() > max {
+                return Err(ImageProxyError::TooLarge(max));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes)
+    }
+
+    fn cache_path(&self, url: &str, width: u32) -> PathBuf {
+        self.config.cache_dir.join(cache_key(url, width))
+    }
+
+    /// キャッシュに書き込み、上限を超えた場合は古いファイルから削除する
+    async fn store(&self, path: &Path, bytes: &[u8]) -> Result<(), ImageProxyError> {
+        tokio::fs::create_dir_all(&self.config.cache_dir).await?;
+
+        // 書き込み途中のファイルを読まれないよう、一時ファイルからリネームする
+        let tmp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+
+        let cache_dir = self.config.cache_dir.clone();
+        let max_cache_bytes = self.config.max_cache_bytes;
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = prune_cache(&cache_dir, max_cache_bytes) {
+                warn!("Failed to prune image cache: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// プロキシ経由の画像パスを生成する
+pub fn proxy_path(source_url: &str, width: u32) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(source_url.as_bytes()).collect();
+    format!("{IMAGE_PROXY_PATH}?url={encoded}&w={}", snap_width(width))
+}
+
+/// 要求された幅を、それ以上で最小の固定幅に丸める
+pub fn snap_width(width: u32) -> u32 {
+    THUMBNAIL_WIDTHS
+        .iter()
+        .copied()
+        .find(|&w| w >= width)
+        .unwrap_or(THUMBNAIL_WIDTHS[THUMBNAIL_WIDTHS.len() - 1])
+}
+
+/// プロキシ対象として許可できるURLか検証する
+///
+/// 内部ネットワークへのリクエストを防ぐため、http(s)以外のスキームや
+/// ループバック・プライベートアドレスは拒否します。ホスト名が指すアドレスは
+/// 取得するときに名前解決して確かめます（リダイレクト先も同じ）。
+pub fn validate_source_url(raw: &str) -> Result<Url, ImageProxyError> {
+    let url = Url::parse(raw).map_err(|e| ImageProxyError::InvalidUrl(e.to_string()))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(ImageProxyError::InvalidUrl(format!(
+            "unsupported scheme: {}",
+            url.scheme()
+        )));
+    }
+
+    match url.host() {
+        None => Err(ImageProxyError::InvalidUrl("missing host".to_string())),
+        Some(url::Host::Domain(domain)) => {
+            let domain = domain.to_lowercase();
+            if domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain.ends_with(".internal")
+            {
+                Err(ImageProxyError::ForbiddenHost(domain))
+            } else {
+                Ok(url)
+            }
+        }
+        Some(url::Host::Ipv4(ip)) if !is_public_ip(IpAddr::V4(ip)) => {
+            Err(ImageProxyError::ForbiddenHost(ip.to_string()))
+        }
+        Some(url::Host::Ipv6(ip)) if !is_public_ip(IpAddr::V6(ip)) => {
+            Err(ImageProxyError::ForbiddenHost(ip.to_string()))
+        }
+        Some(_) => Ok(url),
+    }
+}
+
+/// 名前解決したアドレスから接続先を選ぶ
+///
+/// 公開アドレスと内部のアドレスの両方を返すDNSで検証をすり抜けられないよう、
+/// ひとつでも許可されないアドレスがあれば拒否します。
+fn checked_address(
+    host: &str,
+    addresses: &[SocketAddr],
+    is_allowed_ip: fn(IpAddr) -> bool,
+) -> Result<SocketAddr, ImageProxyError> {
+    if let Some(address) = addresses
+        .iter()
+        .find(|address| !is_allowed_ip(address.ip()))
+    {
+        return Err(ImageProxyError::ForbiddenHost(format!(
+            "{host} ({})",
+            address.ip()
+        )));
+    }
+
+    addresses
+        .first()
+        .copied()
+        .ok_or_else(|| ImageProxyError::FetchFailed(format!("no address found for {host}")))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                // ユニークローカル（fc00::/7）とリンクローカル（fe80::/10）
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+                || ip.to_ipv4_mapped().is_some_and(|v4| !is_public_ip(IpAddr::V4(v4))))
+        }
+    }
+}
+
+fn cache_key(url: &str, width: u32) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    format!("{digest:x}-{width}.jpg")
+}
+
+/// 画像をデコードし、指定幅以下に縮小したJPEGにエンコードする
+pub fn resize_to_jpeg(source: &[u8], width: u32) -> Result<Vec<u8>, ImageProxyError> {
+    let image = image::load_from_memory(source)
+        .map_err(|e| ImageProxyError::DecodeFailed(e.to_string()))?;
+
+    // 元画像より大きくは拡大しない
+    let image = if image.width() > width {
+        let height =
+            (u64::from(image.height()) * u64::from(width) / u64::from(image.width())).max(1) as u32;
+        image.resize_exact(width, height, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .map_err(|e| ImageProxyError::DecodeFailed(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+/// キャッシュディレクトリの合計サイズが上限を超えていれば、古いファイルから削除する
+fn prune_cache(cache_dir: &Path, max_bytes: u64) -> std::io::Result<()> {
+    let mut files = Vec::new();
+    let mut total = 0u64;
+
+    for entry in std::fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        total += metadata.len();
+        files.push((metadata.modified()?, metadata.len(), entry.path()));
+    }
+
+    if total <= max_bytes {
+        return Ok(());
+    }
+
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        std::fs::remove_file(&path)?;
+        total = total.saturating_sub(len);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn sample_png(width: u32, height: u32) -> Vec<u8> {
+        let image = ImageBuffer::from_pixel(width, height, Rgb([200u8, 30, 30]));
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    fn test_config() -> ImageProxyConfig {
+        ImageProxyConfig {
+            cache_dir: std::env::temp_dir()
+                .join(format!("image-proxy-test-{}", uuid::Uuid::new_v4())),
+            ..ImageProxyConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_snap_width() {
+        assert_eq!(snap_width(0), 160);
+        assert_eq!(snap_width(160), 160);
+        assert_eq!(snap_width(200), 320);
+        assert_eq!(snap_width(640), 640);
+        assert_eq!(snap_width(5000), 640);
+    }
+
+    #[test]
+    fn test_proxy_path_encodes_source_url() {
+        let path = proxy_path("https://cdn.example.com/a b.jpg?x=1&y=2", 300);
+        assert_eq!(
+            path,
+            "/images/thumbnail?url=https%3A%2F%2Fcdn.example.com%2Fa+b.jpg%3Fx%3D1%26y%3D2&w=320"
+        );
+    }
+
+    #[test]
+    fn test_validate_source_url() {
+        assert!(validate_source_url("https://cdn.example.com/photo.jpg").is_ok());
+        assert!(validate_source_url("http://93.184.216.34/photo.jpg").is_ok());
+
+        assert!(matches!(
+            validate_source_url("not a url"),
+            Err(ImageProxyError::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            validate_source_url("file:///etc/passwd"),
+            Err(ImageProxyError::InvalidUrl(_))
+        ));
+
+        for forbidden in [
+            "http://localhost/image.png",
+            "http://127.0.0.1/image.png",
+            "http://10.0.0.5/image.png",
+            "http://192.168.1.1/image.png",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/image.png",
+            "http://[fd00::1]/image.png",
+            "http://metadata.google.internal/",
+        ] {
+            let result = validate_source_url(forbidden);
+            assert!(
+                matches!(result, Err(ImageProxyError::ForbiddenHost(_))),
+                "{forbidden} should be forbidden"
+            );
+            assert!(result.unwrap_err().is_client_error());
+        }
+    }
+
+    #[test]
+    fn test_resize_to_jpeg_shrinks_large_images() {
+        let source = sample_png(1000, 500);
+        let jpeg = resize_to_jpeg(&source, 320).unwrap();
+
+        let resized = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!(resized.width(), 320);
+        assert_eq!(resized.height(), 160);
+    }
+
+    #[test]
+    fn test_resize_to_jpeg_does_not_upscale() {
+        let source = sample_png(100, 50);
+        let jpeg = resize_to_jpeg(&source, 640).unwrap();
+
+        let resized = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!(resized.width(), 100);
+        assert_eq!(resized.height(), 50);
+    }
+
+    #[test]
+    fn test_resize_to_jpeg_rejects_broken_image() {
+        let result = resize_to_jpeg(b"definitely not an image", 320);
+        assert!(matches!(result, Err(ImageProxyError::DecodeFailed(_))));
+        assert!(!result.unwrap_err().is_client_error());
+    }
+
+    #[test]
+    fn test_prune_cache_removes_oldest_files() {
+        let dir = std::env::temp_dir().join(format!("image-prune-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let old = dir.join("old.jpg");
+        std::fs::write(&old, vec![0u8; 600]).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        let new = dir.join("new.jpg");
+        std::fs::write(&new, vec![0u8; 600]).unwrap();
+
+        prune_cache(&dir, 1000).unwrap();
+
+        assert!(!old.exists());
+        assert!(new.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checked_address_rejects_any_internal_address() {
+        let public: SocketAddr = "93.184.216.34:443".parse().unwrap();
+        let metadata: SocketAddr = "169.254.169.254:443".parse().unwrap();
+        assert_eq!(
+            checked_address("cdn.example.com", &[public], is_public_ip).unwrap(),
+            public
+        );
+
+        // 公開アドレスと内部のアドレスの両方が返ってきたら拒否する
+        let result = checked_address("cdn.example.com", &[public, metadata], is_public_ip);
+        assert!(matches!(result, Err(ImageProxyError::ForbiddenHost(_))));
+
+        let result = checked_address("cdn.example.com", &[], is_public_ip);
+        assert!(matches!(result, Err(ImageProxyError::FetchFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_names_resolving_to_internal_addresses() {
+        // 名前で弾けないホストも、名前解決したアドレスで拒否する
+        // （localhostは/etc/hostsで解決されるのでネットワークがなくても試せる）
+        let proxy = ImageProxy::new(test_config());
+        let url = Url::parse("http://localhost:8080/image.png").unwrap();
+
+        let result = proxy.resolve(&url).await;
+        assert!(
+            matches!(result, Err(ImageProxyError::ForbiddenHost(_))),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_source_checks_every_redirect() {
+        let server = MockServer::start().await;
+        // 許可されたホストから内部のアドレスへのリダイレクトも拒否する
+        for (from, to) in [
+            ("/metadata", "http://169.254.169.254/latest/meta-data"),
+            ("/internal", "http://metadata.google.internal/"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(from))
+                .respond_with(ResponseTemplate::new(301).insert_header("location", to))
+                .mount(&server)
+                .await;
+        }
+
+        let proxy = ImageProxy::allowing_loopback(test_config());
+        let url = |path: &str| Url::parse(&format!("{}{path}", server.uri())).unwrap();
+
+        for path in ["/metadata", "/internal"] {
+            let result = proxy.fetch_source(&url(path)).await;
+            assert!(
+                matches!(result, Err(ImageProxyError::ForbiddenHost(_))),
+                "{path}: {result:?}"
+            );
+        }
+    }
+
+    // wiremockはループバックで待ち受けるため、URL検証を経由しない内部処理を直接テストする
+    #[tokio::test]
+    async fn test_fetch_source_and_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/photo.png"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "image/png")
+                    .set_body_bytes(sample_png(800, 400)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let proxy = ImageProxy::allowing_loopback(test_config());
+        let url = Url::parse(&format!("{}/photo.png", server.uri())).unwrap();
+
+        let source = proxy.fetch_source(&url).await.unwrap();
+        let thumbnail = resize_to_jpeg(&source, 320).unwrap();
+        let cache_path = proxy.cache_path(url.as_str(), 320);
+        proxy.store(&cache_path, &thumbnail).await.unwrap();
+
+        assert_eq!(tokio::fs::read(&cache_path).await.unwrap(), thumbnail);
+        std::fs::remove_dir_all(&proxy.config().cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_source_rejects_non_image() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/page.html"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string("<html></html>"),
+            )
+            .mount(&server)
+            .await;
+
+        let proxy = ImageProxy::allowing_loopback(test_config());
+        let url = Url::parse(&format!("{}/page.html", server.uri())).unwrap();
+
+        let result = proxy.fetch_source(&url).await;
+        assert!(matches!(
+            result,
+            Err(ImageProxyError::UnsupportedContentType(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_source_enforces_size_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/huge.png"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "image/png")
+                    .set_body_bytes(vec![0u8; 4096]),
+            )
+            .mount(&server)
+            .await;
+
+        let proxy = ImageProxy::allowing_loopback(ImageProxyConfig {
+            max_source_bytes: 1024,
+            ..test_config()
+        });
+        let url = Url::parse(&format!("{}/huge.png", server.uri())).unwrap();
+
+        let result = proxy.fetch_source(&url).await;
+        assert!(matches!(result, Err(ImageProxyError::TooLarge(1024))));
+    }
+}
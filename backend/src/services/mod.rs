@@ -1,3 +1,7 @@
+pub mod image_proxy;
 pub mod translation;
 
-pub use translation::{AmazonTranslateService, TranslationError, TranslationService};
+pub use image_proxy::{ImageProxy, ImageProxyConfig, ImageProxyError};
+pub use translation::{
+    translate_with_metrics, AmazonTranslateService, TranslationError, TranslationService,
+};
@@ -1,6 +1,10 @@
 //! 翻訳サービスの実装
 //!
 //! Amazon Translateを使用して英語から日本語への翻訳を提供します。
+//! 翻訳サービスは[`translate_with_metrics`]を通して呼び出し、呼び出し回数・文字数・
+//! エラーをメトリクスに記録します。
+
+use std::time::Instant;
 
 use async_trait::async_trait;
 use aws_config::BehaviorVersion;
@@ -8,6 +12,8 @@ use aws_sdk_translate::Client as TranslateClient;
 use thiserror::Error;
 use tracing::{debug, error, info};
 
+use crate::metrics::metrics;
+
 #[derive(Error, Debug)]
 pub enum TranslationError {
     #[error("AWS SDK error: {0}")]
@@ -28,6 +34,19 @@ pub trait TranslationService: Send + Sync {
     ) -> Result<String, TranslationError>;
 }
 
+/// 翻訳して、呼び出し回数・文字数・エラー・所要時間をメトリクスに記録する
+pub async fn translate_with_metrics(
+    translator: &dyn TranslationService,
+    text: &str,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<String, TranslationError> {
+    let started = Instant::now();
+    let result = translator.translate(text, source_lang, target_lang).await;
+    metrics().record_translation(text.chars().count(), started.elapsed(), &result);
+    result
+}
+
 pub struct AmazonTranslateService {
     client: TranslateClient,
 }
@@ -0,0 +1,159 @@
+//! グレースフルシャットダウン
+//!
+//! SIGTERM・SIGINT（Ctrl+C）を受け取ったら、新しい接続とスケジュールされたジョブの
+//! 受け付けを止め、処理中のHTTPリクエストと実行中のスクレイピング・翻訳ジョブが
+//! 終わるのを[`ShutdownConfig::timeout`]まで待ちます。
+//!
+//! ジョブは[`BackgroundTasks`]で起動して、終了時に待てるようにします。期限までに
+//! 終わらなかったジョブは中断され、実行中のまま残ります
+//! （[`crate::jobs::STALE_JOB_MINUTES`]が過ぎると止まったものとみなされます）。
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_util::task::TaskTracker;
+use tracing::info;
+
+use crate::utils::env_utils::env_parse;
+
+/// 終了時の設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownConfig {
+    /// シグナルを受け取ってから、処理中のリクエストとジョブを待つ時間
+    ///
+    /// ECSなどが強制終了するまでの時間（ECSのデフォルトは30秒）より短くします。
+    pub timeout: Duration,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(25),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// 環境変数から設定を読み込む（未設定ならデフォルト値）
+    ///
+    /// - `SHUTDOWN_TIMEOUT_SECS`: 処理中のリクエストとジョブを待つ時間（秒）
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            timeout: env_parse("SHUTDOWN_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+        }
+    }
+
+    /// いまシグナルを受け取ったときの期限
+    pub fn deadline(&self) -> Instant {
+        Instant::now() + self.timeout
+    }
+}
+
+/// 終了時に待つバックグラウンドのタスク
+///
+/// クローンしたものは同じタスクの集まりを指します。
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundTasks {
+    tracker: TaskTracker,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// タスクを起動する
+    pub fn spawn<F>(&self, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tracker.spawn(task)
+    }
+
+    /// ほかで起動されるタスク（スケジューラーのジョブなど）を終了時に待つようにする
+    pub fn track<F: Future>(&self, task: F) -> impl Future<Output = F::Output> {
+        self.tracker.track_future(task)
+    }
+
+    /// 実行中のタスクの数
+    pub fn len(&self) -> usize {
+        self.tracker.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracker.is_empty()
+    }
+
+    /// 実行中のタスクが終わるのを`deadline`まで待つ（すべて終わったら`true`）
+    pub async fn drain(&self, deadline: Instant) -> bool {
+        self.tracker.close();
+        tokio::time::timeout_at(deadline, self.tracker.wait())
+            .await
+            .is_ok()
+    }
+}
+
+/// SIGTERMかSIGINT（Ctrl+C）を受け取るまで待つ
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_running_tasks() {
+        let tasks = BackgroundTasks::new();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tasks.spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx.send(()).unwrap();
+        });
+        // ほかで起動したタスクも待つ
+        let tracked = tasks.track(tokio::time::sleep(Duration::from_millis(50)));
+        tokio::spawn(tracked);
+        assert_eq!(tasks.len(), 2);
+
+        assert!(tasks.drain(Instant::now() + Duration::from_secs(5)).await);
+        assert!(tasks.is_empty());
+        rx.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_at_deadline() {
+        let tasks = BackgroundTasks::new();
+        let task = tasks.spawn(std::future::pending::<()>());
+
+        let started = Instant::now();
+        assert!(!tasks.drain(started + Duration::from_millis(50)).await);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(tasks.len(), 1);
+        task.abort();
+    }
+}
@@ -41,10 +41,29 @@ pub mod string_utils {
     }
 }
 
+/// 環境変数から設定を読み込むための関数
+pub mod env_utils {
+    /// 環境変数を読み込んで変換する（未設定か変換できなければ`None`）
+    pub fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+        std::env::var(key).ok().and_then(|value| value.parse().ok())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::env_utils::*;
     use super::string_utils::*;
 
+    #[test]
+    fn test_env_parse() {
+        std::env::set_var("UTILS_TEST_ENV_PARSE", "42");
+        assert_eq!(env_parse::<u32>("UTILS_TEST_ENV_PARSE"), Some(42));
+        // 変換できない値は未設定と同じ
+        assert_eq!(env_parse::<bool>("UTILS_TEST_ENV_PARSE"), None);
+        assert_eq!(env_parse::<u32>("UTILS_TEST_ENV_PARSE_UNSET"), None);
+        std::env::remove_var("UTILS_TEST_ENV_PARSE");
+    }
+
     #[test]
     fn test_to_uppercase() {
         assert_eq!(to_uppercase("hello"), "HELLO");
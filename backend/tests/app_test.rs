@@ -2,28 +2,23 @@ use axum::{
     body::{to_bytes, Body},
     http::{Request, StatusCode},
 };
-use nba_trade_scraper::create_app;
+use chrono::Utc;
+use nba_trade_scraper::{
+    auth::{api_key_prefix, generate_api_key, hash_api_key, Role},
+    create_app,
+    db::{AuthRepository, InMemoryAuthStore, InMemoryNewsStore, NewsRepository},
+    scraper::{NewsItem, NewsSource},
+};
+use std::sync::Arc;
 use tower::ServiceExt;
 
 #[tokio::test]
-#[ignore = "Requires PostgreSQL database connection"]
 async fn test_graphql_playground_endpoint() {
-    // PostgreSQLデータベースが必要
-    let pool = match nba_trade_scraper::db::connection::create_pool().await {
-        Ok(pool) => pool,
-        Err(_) => {
-            eprintln!("Skipping test: PostgreSQL database connection required");
-            return;
-        }
-    };
-    // 結合テスト環境ではinit.sqlでテーブルが作成されるため、マイグレーションはスキップ
-    // sqlx::migrate!("./migrations_postgres")
-    //     .run(&pool)
-    //     .await
-    //     .unwrap();
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
 
     // アプリケーションを作成
-    let app = create_app(pool);
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
 
     // GraphiQLプレイグラウンドにGETリクエスト
     let response = app
@@ -47,24 +42,12 @@ async fn test_graphql_playground_endpoint() {
 }
 
 #[tokio::test]
-#[ignore = "Requires PostgreSQL database connection"]
 async fn test_graphql_post_endpoint() {
-    // PostgreSQLデータベースが必要
-    let pool = match nba_trade_scraper::db::connection::create_pool().await {
-        Ok(pool) => pool,
-        Err(_) => {
-            eprintln!("Skipping test: PostgreSQL database connection required");
-            return;
-        }
-    };
-    // 結合テスト環境ではinit.sqlでテーブルが作成されるため、マイグレーションはスキップ
-    // sqlx::migrate!("./migrations_postgres")
-    //     .run(&pool)
-    //     .await
-    //     .unwrap();
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
 
     // アプリケーションを作成
-    let app = create_app(pool);
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
 
     // GraphQLクエリを送信
     let query = r#"{"query": "{ __typename }"}"#;
@@ -90,24 +73,12 @@ async fn test_graphql_post_endpoint() {
 }
 
 #[tokio::test]
-#[ignore = "Requires PostgreSQL database connection"]
 async fn test_cors_headers() {
-    // PostgreSQLデータベースが必要
-    let pool = match nba_trade_scraper::db::connection::create_pool().await {
-        Ok(pool) => pool,
-        Err(_) => {
-            eprintln!("Skipping test: PostgreSQL database connection required");
-            return;
-        }
-    };
-    // 結合テスト環境ではinit.sqlでテーブルが作成されるため、マイグレーションはスキップ
-    // sqlx::migrate!("./migrations_postgres")
-    //     .run(&pool)
-    //     .await
-    //     .unwrap();
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
 
     // アプリケーションを作成
-    let app = create_app(pool);
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
 
     // CORSヘッダーを持つリクエスト
     let response = app
@@ -130,24 +101,12 @@ async fn test_cors_headers() {
 }
 
 #[tokio::test]
-#[ignore = "Requires PostgreSQL database connection"]
 async fn test_create_app_routes() {
-    // PostgreSQLデータベースが必要
-    let pool = match nba_trade_scraper::db::connection::create_pool().await {
-        Ok(pool) => pool,
-        Err(_) => {
-            eprintln!("Skipping test: PostgreSQL database connection required");
-            return;
-        }
-    };
-    // 結合テスト環境ではinit.sqlでテーブルが作成されるため、マイグレーションはスキップ
-    // sqlx::migrate!("./migrations_postgres")
-    //     .run(&pool)
-    //     .await
-    //     .unwrap();
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
 
     // アプリケーションを作成
-    let app = create_app(pool);
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
 
     // 存在しないルートへのリクエスト
     let response = app
@@ -164,3 +123,246 @@ async fn test_create_app_routes() {
     // 404が返ることを確認
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_api_key_authentication() {
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
+    let auth = Arc::new(InMemoryAuthStore::new());
+    let key = generate_api_key();
+    auth.create_api_key(
+        "admin",
+        Role::Admin,
+        &hash_api_key(&key),
+        &api_key_prefix(&key),
+    )
+    .await
+    .unwrap();
+
+    // アプリケーションを作成
+    let app = create_app(repository, auth);
+
+    let query = r#"{"query": "{ apiKeys { name role lastUsedAt } }"}"#;
+    let request = |api_key: &str| {
+        Request::builder()
+            .uri("/")
+            .method("POST")
+            .header("content-type", "application/json")
+            .header("x-api-key", api_key)
+            .body(Body::from(query))
+            .unwrap()
+    };
+
+    // 正しいキーならadmin用のフィールドを読める
+    let response = app.clone().oneshot(request(&key)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["apiKeys"][0]["name"], "admin");
+    assert!(json["data"]["apiKeys"][0]["lastUsedAt"].is_string());
+
+    // 誤ったキーは401
+    let response = app.oneshot(request("ntk_invalid")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["errors"][0]["extensions"]["code"], "UNAUTHENTICATED");
+}
+
+#[tokio::test]
+async fn test_graphql_get_endpoint_supports_conditional_requests() {
+    let repository = Arc::new(InMemoryNewsStore::new());
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
+    let get = |if_none_match: Option<&str>| {
+        let mut builder = Request::builder()
+            .uri("/?query=%7B%20teams%20%7B%20code%20%7D%20%7D")
+            .method("GET");
+        if let Some(etag) = if_none_match {
+            builder = builder.header("if-none-match", etag);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = app.clone().oneshot(get(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("cache-control"));
+    let etag = response
+        .headers()
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["teams"].as_array().unwrap().len(), 30);
+
+    let response = app.oneshot(get(Some(&etag))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn test_rest_api_endpoints() {
+    let repository = Arc::new(InMemoryNewsStore::new());
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
+    let get = |uri: &str, api_key: Option<&str>| {
+        let mut builder = Request::builder().uri(uri).method("GET");
+        if let Some(key) = api_key {
+            builder = builder.header("x-api-key", key);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(get("/api/v1/teams?conference=east", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 15);
+
+    let response = app
+        .clone()
+        .oneshot(get("/api/v1/openapi.json", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["paths"]["/api/v1/news"].is_object());
+
+    // GraphQLと同じ認証を通る
+    let response = app
+        .oneshot(get("/api/v1/news", Some("ntk_invalid")))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let repository = Arc::new(InMemoryNewsStore::new());
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
+
+    // 操作名付きのGraphQLリクエストを1件処理する
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"query":"query MetricsEndpointTest { teams { id } }"}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains(
+        r#"nba_graphql_request_duration_seconds_count{operation="MetricsEndpointTest",status="ok"} 1"#
+    ));
+}
+
+#[tokio::test]
+async fn test_graphql_post_endpoint_uses_accept_language() {
+    let repository = Arc::new(InMemoryNewsStore::new());
+    repository
+        .save_news_items(vec![NewsItem {
+            id: "locale-1".to_string(),
+            title: "Lakers acquire guard".to_string(),
+            description: None,
+            link: "https://example.com/locale-1".to_string(),
+            source: NewsSource::ESPN,
+            category: "Trade".to_string(),
+            published_at: Utc::now(),
+            image_url: None,
+            thumbnail_url: None,
+        }])
+        .await
+        .unwrap();
+    repository
+        .save_translation("locale-1", "レイカーズがガードを獲得", None)
+        .await
+        .unwrap();
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
+
+    let query =
+        r#"{"query": "{ tradeNews { title categoryLabel } team(code: \"LAL\") { fullName } }"}"#;
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .method("POST")
+                .header("content-type", "application/json")
+                .header("accept-language", "ja-JP,ja;q=0.9,en;q=0.8")
+                .body(Body::from(query))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        json["data"]["tradeNews"][0]["title"],
+        "レイカーズがガードを獲得"
+    );
+    assert_eq!(json["data"]["tradeNews"][0]["categoryLabel"], "トレード");
+    assert_eq!(json["data"]["team"]["fullName"], "ロサンゼルス・レイカーズ");
+}
+
+#[tokio::test]
+async fn test_graphql_ws_endpoint_accepts_subscriptions() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let app = create_app(
+        Arc::new(InMemoryNewsStore::new()),
+        Arc::new(InMemoryAuthStore::new()),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    // graphql-wsのプロトコルでWebSocketにアップグレードできる
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "GET /ws HTTP/1.1\r\nHost: {addr}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+                 Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                 Sec-WebSocket-Protocol: graphql-transport-ws\r\nAccept-Language: ja\r\n\r\n"
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut buf = vec![0; 1024];
+    let n = stream.read(&mut buf).await.unwrap();
+    let response = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+    assert!(response.starts_with("http/1.1 101"), "{response}");
+    assert!(response.contains("sec-websocket-protocol: graphql-transport-ws"));
+}
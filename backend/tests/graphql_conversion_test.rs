@@ -12,6 +12,8 @@ fn test_news_item_to_trade_news_conversion() {
         source: NewsSource::ESPN,
         category: "Signing".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
 
     let trade_news = TradeNews::from(news_item.clone());
@@ -36,6 +38,8 @@ fn test_news_source_variants() {
         source: NewsSource::ESPN,
         category: "Other".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let espn_trade = TradeNews::from(espn_item);
     assert_eq!(espn_trade.source, "ESPN");
@@ -49,6 +53,8 @@ fn test_news_source_variants() {
         source: NewsSource::RealGM,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let realgm_trade = TradeNews::from(realgm_item);
     assert_eq!(realgm_trade.source, "RealGM");
@@ -62,6 +68,8 @@ fn test_news_source_variants() {
         source: NewsSource::HoopsHype,
         category: "Signing".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let hoopshype_trade = TradeNews::from(hoopshype_item);
     assert_eq!(hoopshype_trade.source, "HoopsHype");
@@ -75,6 +83,8 @@ fn test_news_source_variants() {
         source: NewsSource::Other("Custom Source".to_string()),
         category: "Other".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let other_trade = TradeNews::from(other_item);
     assert_eq!(other_trade.source, "Custom Source");
@@ -91,6 +101,8 @@ fn test_optional_description() {
         source: NewsSource::ESPN,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let trade_with_desc = TradeNews::from(with_desc);
     assert_eq!(
@@ -107,6 +119,8 @@ fn test_optional_description() {
         source: NewsSource::ESPN,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
     let trade_without_desc = TradeNews::from(without_desc);
     assert_eq!(trade_without_desc.description, None);
@@ -2,28 +2,20 @@ use axum::{
     body::{to_bytes, Body},
     http::{Request, StatusCode},
 };
-use nba_trade_scraper::create_app;
+use nba_trade_scraper::{
+    create_app,
+    db::{InMemoryAuthStore, InMemoryNewsStore},
+};
+use std::sync::Arc;
 use tower::ServiceExt;
 
 #[tokio::test]
-#[ignore = "Requires PostgreSQL database connection"]
 async fn test_health_check_endpoint() {
-    // PostgreSQLデータベースが必要
-    let pool = match nba_trade_scraper::db::connection::create_pool().await {
-        Ok(pool) => pool,
-        Err(_) => {
-            eprintln!("Skipping test: PostgreSQL database connection required");
-            return;
-        }
-    };
-    // 結合テスト環境ではinit.sqlでテーブルが作成されるため、マイグレーションはスキップ
-    // sqlx::migrate!("./migrations_postgres")
-    //     .run(&pool)
-    //     .await
-    //     .unwrap();
+    // データベースの代わりにインメモリのストアを使う
+    let repository = Arc::new(InMemoryNewsStore::new());
 
     // アプリケーションを作成
-    let app = create_app(pool);
+    let app = create_app(repository, Arc::new(InMemoryAuthStore::new()));
 
     // ヘルスチェックエンドポイントにリクエスト
     let response = app
@@ -49,3 +41,30 @@ async fn test_health_check_endpoint() {
     assert_eq!(json["status"], "healthy");
     assert_eq!(json["service"], "nba-trade-scraper");
 }
+
+#[tokio::test]
+async fn test_liveness_and_readiness_endpoints() {
+    let app = create_app(
+        Arc::new(InMemoryNewsStore::new()),
+        Arc::new(InMemoryAuthStore::new()),
+    );
+
+    for (uri, status) in [
+        ("/health/live", "healthy"),
+        ("/health/ready", "healthy"),
+        // まだスクレイピングしていない
+        ("/health/deep", "degraded"),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], status, "{}", uri);
+        assert_eq!(json["service"], "nba-trade-scraper", "{}", uri);
+    }
+}
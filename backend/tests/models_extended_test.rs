@@ -117,6 +117,8 @@ fn test_news_item_id_generation() {
         source: NewsSource::ESPN,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
 
     let item2 = NewsItem {
@@ -127,6 +129,8 @@ fn test_news_item_id_generation() {
         source: NewsSource::ESPN,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
 
     // IDが異なることを確認
@@ -169,6 +173,8 @@ fn test_news_item_serialization() {
         source: NewsSource::ESPN,
         category: "Trade".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
 
     // JSONにシリアライズ
@@ -197,6 +203,8 @@ fn test_news_item_with_none_description() {
         source: NewsSource::RealGM,
         category: "Other".to_string(),
         published_at: Utc::now(),
+        image_url: None,
+        thumbnail_url: None,
     };
 
     // JSONにシリアライズしてデシリアライズ
@@ -29,7 +29,7 @@ export const GET_NEWS_BY_CATEGORY = gql`
 `;
 
 export const GET_NEWS_BY_SOURCE = gql`
-  query GetNewsBySource($source: String!) {
+  query GetNewsBySource($source: NewsSource!) {
     tradeNewsBySource(source: $source) {
       id
       title
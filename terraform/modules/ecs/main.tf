@@ -199,7 +199,7 @@ resource "aws_lb_target_group" "main" {
     healthy_threshold   = 2
     interval            = 30
     matcher             = "200"
-    path                = "/"
+    path                = "/health/ready"
     port                = "traffic-port"
     protocol            = "HTTP"
     timeout             = 5
@@ -285,6 +285,11 @@ resource "aws_ecs_task_definition" "main" {
         {
           name  = "DATABASE_URL"
           value = var.database_url
+        },
+        {
+          # ALBの背後にあるため、レート制限はX-Forwarded-ForのIPで行う
+          name  = "TRUST_FORWARDED_FOR"
+          value = "true"
         }
       ]
 
@@ -305,7 +310,7 @@ resource "aws_ecs_task_definition" "main" {
       }
 
       healthCheck = {
-        command     = ["CMD-SHELL", "curl -f http://localhost:8000/health || exit 1"]
+        command     = ["CMD-SHELL", "curl -f http://localhost:8000/health/live || exit 1"]
         interval    = 30
         timeout     = 5
         retries     = 3